@@ -0,0 +1,631 @@
+//
+//  Command
+//! Parses commands typed into the command line while the emulator is running.
+//
+
+
+use std::io;
+use std::thread::Thread;
+use std::sync::mpsc::{channel, Receiver};
+
+use minion;
+use config;
+use config::{FaultConfig, Wire, Adjacency, Mount};
+
+
+/// The help text listing every command.
+pub const HELP: &'static str = concat!(
+	"Commands:\n",
+	"  cable <id> <side> <cable>       Attach a wired modem connected to a cable network.\n",
+	"  detach <id> <side>              Remove the peripheral on a side of a computer.\n",
+	"  attach <cable> <type>           Place a modem or speaker on a cable network.\n",
+	"  remove <cable> <name>           Remove a peripheral from a cable network.\n",
+	"  peripherals <cable>             List the peripherals on a cable network.\n",
+	"  position <id> <x> <y> <z>       Move a computer to a position in the world.\n",
+	"  gps <x> <y> <z>                 Start four GPS hosts around a position.\n",
+	"  network <id> <side> <network>   Move a wireless modem onto a named network.\n",
+	"  bridge <network> <network>      Bridge two wireless networks together.\n",
+	"  unbridge <network> <network>    Remove the bridge between two networks.\n",
+	"  sniff                           Toggle printing modem traffic to the console.\n",
+	"  transmit <network> <channel> <reply> <message>\n",
+	"                                  Send a message, written as a Lua value, on a network.\n",
+	"  subscribe <network> <channel>   Print messages sent on a channel of a network.\n",
+	"  unsubscribe <network> <channel> Stop printing messages sent on a channel.\n",
+	"  faults <network> [<delay> <drop> <reorder> [seed]]\n",
+	"                                  Show or set the faults applied to a network's messages.\n",
+	"  redstone <id> [<side> <level>]  Show a computer's redstone, or set an input from 0 to 15.\n",
+	"  bundled <id> <side> <colors>    Set the colors entering a side through bundled cable.\n",
+	"  wire <id> <side> <id> <side>    Wire a computer's redstone output into another's input.\n",
+	"  unwire <id> <side> <id> <side>  Remove a redstone wire.\n",
+	"  wires                           List the redstone wires.\n",
+	"  adjacent <id> <side> <id> <side>\n",
+	"                                  Place two computers next to each other.\n",
+	"  mount <id> <ro|rw> <path> <directory>\n",
+	"                                  Mount a host directory in a computer, from its next boot.\n",
+	"  unmount <id> <path>             Remove a mounted directory, from the computer's next boot.\n",
+	"  mounts <id>                     List the directories mounted in a computer.\n",
+	"  export <id> [file]              Save a computer's files and label to a zip archive.\n",
+	"  import <file> [id] [replace|merge]\n",
+	"                                  Load an archive into a computer, or a new one.\n",
+	"  snapshot <id>                   Copy a computer's files into a new snapshot.\n",
+	"  snapshots <id>                  List a computer's snapshots with their times and sizes.\n",
+	"  rollback <id> [snapshot]        Restore a snapshot, by default the latest.\n",
+	"  unsnapshot <id> <snapshot>      Delete a snapshot.\n",
+	"  view <id>                       Open the world view around a turtle.\n",
+	"  help                            Show this message.",
+);
+
+
+/// A command for the emulator to perform.
+#[derive(PartialEq, Show)]
+pub enum Command {
+	/// Arguments: computer ID, side, cable name
+	Cable(u32, String, String),
+
+	/// Arguments: computer ID, side
+	Detach(u32, String),
+
+	/// Arguments: cable name, peripheral type
+	Attach(String, String),
+
+	/// Arguments: cable name, peripheral name
+	Remove(String, String),
+
+	/// Arguments: cable name
+	Peripherals(String),
+
+	/// Arguments: computer ID, x, y, z
+	Position(u32, f64, f64, f64),
+
+	/// Arguments: x, y, z
+	Gps(f64, f64, f64),
+
+	/// Arguments: computer ID, side, network name
+	Network(u32, String, String),
+
+	/// Arguments: network name, network name
+	Bridge(String, String),
+
+	/// Arguments: network name, network name
+	Unbridge(String, String),
+
+	Sniff,
+
+	/// Arguments: network name, channel, reply channel, message as a Lua value
+	Transmit(String, u16, u16, String),
+
+	/// Arguments: network name, channel
+	Subscribe(String, u16),
+
+	/// Arguments: network name, channel
+	Unsubscribe(String, u16),
+
+	/// Arguments: network name, new faults or None to show the current ones
+	Faults(String, Option<FaultConfig>),
+
+	/// Arguments: computer ID, side and level to set, or None to show the
+	/// computer's redstone
+	Redstone(u32, Option<(String, u8)>),
+
+	/// Arguments: computer ID, side, colors
+	Bundled(u32, String, u16),
+
+	Wire(Wire),
+
+	Unwire(Wire),
+
+	Wires,
+
+	Adjacent(Adjacency),
+
+	Mount(u32, Mount),
+
+	/// Arguments: computer ID, path inside the computer
+	Unmount(u32, String),
+
+	/// Arguments: computer ID
+	Mounts(u32),
+
+	/// Arguments: computer ID, archive path or None for the default
+	Export(u32, Option<String>),
+
+	/// Arguments: archive path, computer ID or None for a new computer, how to
+	/// handle existing files
+	Import(String, Option<u32>, String),
+
+	/// Arguments: computer ID
+	Snapshot(u32),
+
+	/// Arguments: computer ID
+	Snapshots(u32),
+
+	/// Arguments: computer ID, snapshot name or None for the latest
+	Rollback(u32, Option<String>),
+
+	/// Arguments: computer ID, snapshot name
+	Unsnapshot(u32, String),
+
+	/// Arguments: turtle ID
+	View(u32),
+
+	Help,
+}
+
+
+/// Returns an error if the command doesn't have the given number of arguments.
+fn expect(words: &[&str], count: usize, usage: &str) -> Result<(), String> {
+	if words.len() - 1 != count {
+		Err(format!("Usage: {}", usage))
+	} else {
+		Ok(())
+	}
+}
+
+/// Parses a computer ID.
+fn id(word: &str) -> Result<u32, String> {
+	word.parse::<u32>().ok_or(format!("Invalid computer ID `{}`.", word))
+}
+
+/// Parses a coordinate.
+fn coordinate(word: &str) -> Result<f64, String> {
+	word.parse::<f64>().ok_or(format!("Invalid coordinate `{}`.", word))
+}
+
+/// Parses a modem channel.
+fn channel(word: &str) -> Result<u16, String> {
+	word.parse::<u16>().ok_or(format!("Invalid channel `{}`. Expected a number from 0 to 65535.",
+		word))
+}
+
+/// Parses a probability from 0 to 1.
+fn probability(word: &str) -> Result<f64, String> {
+	match word.parse::<f64>() {
+		Some(value) if value >= 0.0 && value <= 1.0 => Ok(value),
+		_ => Err(format!("Invalid probability `{}`. Expected a number from 0 to 1.", word)),
+	}
+}
+
+/// Parses the seed for a network's faults.
+fn seed(word: &str) -> Result<u64, String> {
+	match word.parse::<u64>() {
+		Some(value) if value <= config::MAX_SEED => Ok(value),
+		_ => Err(format!("Invalid seed `{}`. Expected a number from 0 to {}.", word,
+			config::MAX_SEED)),
+	}
+}
+
+/// Parses a redstone signal strength.
+fn level(word: &str) -> Result<u8, String> {
+	match word.parse::<u8>() {
+		Some(value) if value <= minion::MAX_REDSTONE => Ok(value),
+		_ => Err(format!("Invalid redstone level `{}`. Expected a number from 0 to {}.", word,
+			minion::MAX_REDSTONE)),
+	}
+}
+
+/// Parses the four arguments to a wire command.
+fn wire(words: &[&str], usage: &str) -> Result<Wire, String> {
+	try!(expect(words, 4, usage));
+	Ok(Wire {
+		from: try!(id(words[1])),
+		from_side: try!(side(words[2])),
+		to: try!(id(words[3])),
+		to_side: try!(side(words[4])),
+	})
+}
+
+/// Returns the rest of the line after skipping the given number of words.
+fn rest(line: &str, count: usize) -> &str {
+	let mut remaining = line.trim_left();
+	for _ in range(0, count) {
+		let end = remaining.find(|&: c: char| c.is_whitespace()).unwrap_or(remaining.len());
+		remaining = remaining.slice_from(end).trim_left();
+	}
+	remaining
+}
+
+/// Parses the name of a side of a computer.
+fn side(word: &str) -> Result<String, String> {
+	if minion::SIDES.iter().any(|name| *name == word) {
+		Ok(word.to_string())
+	} else {
+		Err(format!("Invalid side `{}`. Expected one of {}.", word,
+			minion::SIDES.connect(", ")))
+	}
+}
+
+/// Parses a single line of input into a command.
+pub fn parse(line: &str) -> Result<Command, String> {
+	let words: Vec<&str> = line.words().collect();
+	if words.len() == 0 {
+		return Err("No command given.".to_string());
+	}
+
+	match words[0] {
+		"cable" => {
+			try!(expect(words.as_slice(), 3, "cable <id> <side> <cable>"));
+			Ok(Command::Cable(try!(id(words[1])), try!(side(words[2])),
+				words[3].to_string()))
+		},
+		"detach" => {
+			try!(expect(words.as_slice(), 2, "detach <id> <side>"));
+			Ok(Command::Detach(try!(id(words[1])), try!(side(words[2]))))
+		},
+		"attach" => {
+			try!(expect(words.as_slice(), 2, "attach <cable> <type>"));
+			Ok(Command::Attach(words[1].to_string(), words[2].to_string()))
+		},
+		"remove" => {
+			try!(expect(words.as_slice(), 2, "remove <cable> <name>"));
+			Ok(Command::Remove(words[1].to_string(), words[2].to_string()))
+		},
+		"peripherals" => {
+			try!(expect(words.as_slice(), 1, "peripherals <cable>"));
+			Ok(Command::Peripherals(words[1].to_string()))
+		},
+		"position" => {
+			try!(expect(words.as_slice(), 4, "position <id> <x> <y> <z>"));
+			Ok(Command::Position(try!(id(words[1])), try!(coordinate(words[2])),
+				try!(coordinate(words[3])), try!(coordinate(words[4]))))
+		},
+		"gps" => {
+			try!(expect(words.as_slice(), 3, "gps <x> <y> <z>"));
+			Ok(Command::Gps(try!(coordinate(words[1])), try!(coordinate(words[2])),
+				try!(coordinate(words[3]))))
+		},
+		"network" => {
+			try!(expect(words.as_slice(), 3, "network <id> <side> <network>"));
+			Ok(Command::Network(try!(id(words[1])), try!(side(words[2])),
+				words[3].to_string()))
+		},
+		"bridge" => {
+			try!(expect(words.as_slice(), 2, "bridge <network> <network>"));
+			Ok(Command::Bridge(words[1].to_string(), words[2].to_string()))
+		},
+		"unbridge" => {
+			try!(expect(words.as_slice(), 2, "unbridge <network> <network>"));
+			Ok(Command::Unbridge(words[1].to_string(), words[2].to_string()))
+		},
+		"sniff" => Ok(Command::Sniff),
+		"transmit" => {
+			if words.len() < 5 {
+				return Err("Usage: transmit <network> <channel> <reply> <message>".to_string());
+			}
+
+			Ok(Command::Transmit(words[1].to_string(), try!(channel(words[2])),
+				try!(channel(words[3])), rest(line, 4).to_string()))
+		},
+		"subscribe" => {
+			try!(expect(words.as_slice(), 2, "subscribe <network> <channel>"));
+			Ok(Command::Subscribe(words[1].to_string(), try!(channel(words[2]))))
+		},
+		"unsubscribe" => {
+			try!(expect(words.as_slice(), 2, "unsubscribe <network> <channel>"));
+			Ok(Command::Unsubscribe(words[1].to_string(), try!(channel(words[2]))))
+		},
+		"faults" => {
+			if words.len() == 2 {
+				return Ok(Command::Faults(words[1].to_string(), None));
+			} else if words.len() != 5 && words.len() != 6 {
+				return Err("Usage: faults <network> [<delay> <drop> <reorder> [seed]]".to_string());
+			}
+
+			let delay = try!(words[2].parse::<u32>()
+				.ok_or(format!("Invalid delay `{}`. Expected a number of ticks.", words[2])));
+			let seed = if words.len() == 6 {
+				Some(try!(seed(words[5])))
+			} else {
+				None
+			};
+
+			Ok(Command::Faults(words[1].to_string(), Some(FaultConfig {
+				delay: delay,
+				drop: try!(probability(words[3])),
+				reorder: try!(probability(words[4])),
+				seed: seed,
+			})))
+		},
+		"redstone" => {
+			if words.len() == 2 {
+				return Ok(Command::Redstone(try!(id(words[1])), None));
+			}
+
+			try!(expect(words.as_slice(), 3, "redstone <id> [<side> <level>]"));
+			Ok(Command::Redstone(try!(id(words[1])),
+				Some((try!(side(words[2])), try!(level(words[3]))))))
+		},
+		"bundled" => {
+			try!(expect(words.as_slice(), 3, "bundled <id> <side> <colors>"));
+			let colors = try!(words[3].parse::<u16>()
+				.ok_or(format!("Invalid colors `{}`. Expected a number from 0 to 65535.",
+					words[3])));
+			Ok(Command::Bundled(try!(id(words[1])), try!(side(words[2])), colors))
+		},
+		"wire" => Ok(Command::Wire(try!(wire(words.as_slice(),
+			"wire <id> <side> <id> <side>")))),
+		"unwire" => Ok(Command::Unwire(try!(wire(words.as_slice(),
+			"unwire <id> <side> <id> <side>")))),
+		"wires" => Ok(Command::Wires),
+		"adjacent" => {
+			try!(expect(words.as_slice(), 4, "adjacent <id> <side> <id> <side>"));
+			Ok(Command::Adjacent(Adjacency {
+				first: try!(id(words[1])),
+				first_side: try!(side(words[2])),
+				second: try!(id(words[3])),
+				second_side: try!(side(words[4])),
+			}))
+		},
+		"mount" => {
+			let usage = "Usage: mount <id> <ro|rw> <path> <directory>";
+			if words.len() < 5 {
+				return Err(usage.to_string());
+			}
+
+			let read_only = match words[2] {
+				"ro" => true,
+				"rw" => false,
+				_ => return Err(usage.to_string()),
+			};
+
+			Ok(Command::Mount(try!(id(words[1])), Mount {
+				path: words[3].to_string(),
+				directory: rest(line, 4).to_string(),
+				read_only: read_only,
+			}))
+		},
+		"unmount" => {
+			try!(expect(words.as_slice(), 2, "unmount <id> <path>"));
+			Ok(Command::Unmount(try!(id(words[1])), words[2].to_string()))
+		},
+		"mounts" => {
+			try!(expect(words.as_slice(), 1, "mounts <id>"));
+			Ok(Command::Mounts(try!(id(words[1]))))
+		},
+		"export" => {
+			if words.len() != 2 && words.len() != 3 {
+				return Err("Usage: export <id> [file]".to_string());
+			}
+
+			Ok(Command::Export(try!(id(words[1])), words.get(2).map(|file| file.to_string())))
+		},
+		"import" => {
+			let usage = "Usage: import <file> [id] [replace|merge]";
+			if words.len() < 2 || words.len() > 4 {
+				return Err(usage.to_string());
+			}
+
+			// The ID and mode are both optional
+			let mut computer = None;
+			let mut mode = "fail".to_string();
+			for word in words.slice_from(2).iter() {
+				match *word {
+					"replace" | "merge" => mode = word.to_string(),
+					_ if computer.is_none() => computer = Some(try!(id(*word))),
+					_ => return Err(usage.to_string()),
+				}
+			}
+
+			Ok(Command::Import(words[1].to_string(), computer, mode))
+		},
+		"snapshot" => {
+			try!(expect(words.as_slice(), 1, "snapshot <id>"));
+			Ok(Command::Snapshot(try!(id(words[1]))))
+		},
+		"snapshots" => {
+			try!(expect(words.as_slice(), 1, "snapshots <id>"));
+			Ok(Command::Snapshots(try!(id(words[1]))))
+		},
+		"rollback" => {
+			if words.len() != 2 && words.len() != 3 {
+				return Err("Usage: rollback <id> [snapshot]".to_string());
+			}
+
+			Ok(Command::Rollback(try!(id(words[1])), words.get(2).map(|name| name.to_string())))
+		},
+		"unsnapshot" => {
+			try!(expect(words.as_slice(), 2, "unsnapshot <id> <snapshot>"));
+			Ok(Command::Unsnapshot(try!(id(words[1])), words[2].to_string()))
+		},
+		"view" => {
+			try!(expect(words.as_slice(), 1, "view <id>"));
+			Ok(Command::View(try!(id(words[1]))))
+		},
+		"help" => Ok(Command::Help),
+		_ => Err(format!("Unknown command `{}`. Type `help` for a list of commands.",
+			words[0])),
+	}
+}
+
+
+/// Reads lines typed into standard input on a background thread.
+pub struct Console {
+	receiver: Receiver<String>,
+}
+
+
+impl Console {
+
+	/// Start reading from standard input.
+	pub fn new() -> Console {
+		let (sender, receiver) = channel();
+
+		Thread::spawn(move || {
+			for line in io::stdin().lock().lines() {
+				match line {
+					Ok(text) => if sender.send(text).is_err() {
+						break;
+					},
+					Err(_) => break,
+				}
+			}
+		});
+
+		Console {
+			receiver: receiver,
+		}
+	}
+
+	/// Returns every non-empty line typed since the last call, without blocking.
+	pub fn lines(&self) -> Vec<String> {
+		let mut lines = Vec::new();
+		while let Ok(line) = self.receiver.try_recv() {
+			let trimmed = line.as_slice().trim().to_string();
+			if trimmed.len() > 0 {
+				lines.push(trimmed);
+			}
+		}
+		lines
+	}
+
+}
+
+
+#[cfg(test)]
+mod test {
+	use super::{parse, Command};
+	use config::{FaultConfig, Wire, Adjacency, Mount};
+
+	#[test]
+	fn faults() {
+		assert_eq!(parse("faults default"), Ok(Command::Faults("default".to_string(), None)));
+		assert_eq!(parse("faults default 2 0.1 0.05 1234"), Ok(Command::Faults(
+			"default".to_string(),
+			Some(FaultConfig {
+				delay: 2,
+				drop: 0.1,
+				reorder: 0.05,
+				seed: Some(1234),
+			}),
+		)));
+		assert_eq!(parse("faults default 0 0 0"), Ok(Command::Faults(
+			"default".to_string(),
+			Some(FaultConfig {
+				delay: 0,
+				drop: 0.0,
+				reorder: 0.0,
+				seed: None,
+			}),
+		)));
+	}
+
+	#[test]
+	fn invalid_faults() {
+		assert!(parse("faults").is_err());
+		assert!(parse("faults default 2").is_err());
+		assert!(parse("faults default -1 0 0").is_err());
+		assert!(parse("faults default 0 1.5 0").is_err());
+		assert!(parse("faults default 0 0 0 -1").is_err());
+	}
+
+	#[test]
+	fn seed_fits_in_java_long() {
+		assert!(parse("faults default 0 0 0 9223372036854775807").is_ok());
+		assert!(parse("faults default 0 0 0 9223372036854775808").is_err());
+		assert!(parse("faults default 0 0 0 18446744073709551615").is_err());
+	}
+
+	#[test]
+	fn transmit() {
+		assert_eq!(parse("transmit default 1 2 \"hello  there\""), Ok(Command::Transmit(
+			"default".to_string(), 1, 2, "\"hello  there\"".to_string())));
+		assert_eq!(parse("transmit default 1 2   {[1] = 2}"), Ok(Command::Transmit(
+			"default".to_string(), 1, 2, "{[1] = 2}".to_string())));
+		assert!(parse("transmit default 1 2").is_err());
+		assert!(parse("transmit default 65536 2 nil").is_err());
+	}
+
+	#[test]
+	fn redstone() {
+		assert_eq!(parse("redstone 3"), Ok(Command::Redstone(3, None)));
+		assert_eq!(parse("redstone 3 left 15"),
+			Ok(Command::Redstone(3, Some(("left".to_string(), 15)))));
+		assert!(parse("redstone 3 left 16").is_err());
+		assert!(parse("redstone 3 sideways 1").is_err());
+		assert!(parse("redstone 3 left").is_err());
+
+		assert_eq!(parse("bundled 0 back 65535"),
+			Ok(Command::Bundled(0, "back".to_string(), 65535)));
+		assert!(parse("bundled 0 back 65536").is_err());
+	}
+
+	#[test]
+	fn wires() {
+		let wire = Wire {
+			from: 0,
+			from_side: "right".to_string(),
+			to: 1,
+			to_side: "left".to_string(),
+		};
+		assert_eq!(parse("wire 0 right 1 left"), Ok(Command::Wire(wire.clone())));
+		assert_eq!(parse("unwire 0 right 1 left"), Ok(Command::Unwire(wire)));
+		assert_eq!(parse("wires"), Ok(Command::Wires));
+		assert!(parse("wire 0 right 1").is_err());
+		assert!(parse("wire 0 right one left").is_err());
+		assert!(parse("wire 0 up 1 left").is_err());
+	}
+
+	#[test]
+	fn adjacent() {
+		assert_eq!(parse("adjacent 0 right 1 left"), Ok(Command::Adjacent(Adjacency {
+			first: 0,
+			first_side: "right".to_string(),
+			second: 1,
+			second_side: "left".to_string(),
+		})));
+		assert!(parse("adjacent 0 right 1").is_err());
+		assert!(parse("adjacent 0 right 1 middle").is_err());
+	}
+
+	#[test]
+	fn mounts() {
+		assert_eq!(parse("mount 2 ro lib projects/my lib"), Ok(Command::Mount(2, Mount {
+			path: "lib".to_string(),
+			directory: "projects/my lib".to_string(),
+			read_only: true,
+		})));
+		assert_eq!(parse("mount 2 rw data /tmp/data"), Ok(Command::Mount(2, Mount {
+			path: "data".to_string(),
+			directory: "/tmp/data".to_string(),
+			read_only: false,
+		})));
+		assert!(parse("mount 2 rx lib lib").is_err());
+		assert!(parse("mount 2 ro lib").is_err());
+
+		assert_eq!(parse("unmount 2 lib"), Ok(Command::Unmount(2, "lib".to_string())));
+		assert_eq!(parse("mounts 2"), Ok(Command::Mounts(2)));
+	}
+
+	#[test]
+	fn archives() {
+		assert_eq!(parse("export 1"), Ok(Command::Export(1, None)));
+		assert_eq!(parse("export 1 backup.zip"),
+			Ok(Command::Export(1, Some("backup.zip".to_string()))));
+		assert!(parse("export").is_err());
+
+		assert_eq!(parse("import a.zip"),
+			Ok(Command::Import("a.zip".to_string(), None, "fail".to_string())));
+		assert_eq!(parse("import a.zip 4"),
+			Ok(Command::Import("a.zip".to_string(), Some(4), "fail".to_string())));
+		assert_eq!(parse("import a.zip replace"),
+			Ok(Command::Import("a.zip".to_string(), None, "replace".to_string())));
+		assert_eq!(parse("import a.zip 4 merge"),
+			Ok(Command::Import("a.zip".to_string(), Some(4), "merge".to_string())));
+		assert_eq!(parse("import a.zip merge 4"),
+			Ok(Command::Import("a.zip".to_string(), Some(4), "merge".to_string())));
+		assert!(parse("import a.zip 4 5").is_err());
+		assert!(parse("import a.zip overwrite").is_err());
+		assert!(parse("import").is_err());
+	}
+
+	#[test]
+	fn snapshots() {
+		assert_eq!(parse("snapshot 0"), Ok(Command::Snapshot(0)));
+		assert_eq!(parse("snapshots 0"), Ok(Command::Snapshots(0)));
+		assert_eq!(parse("rollback 0"), Ok(Command::Rollback(0, None)));
+		assert_eq!(parse("rollback 0 3"), Ok(Command::Rollback(0, Some("3".to_string()))));
+		assert_eq!(parse("unsnapshot 0 3"), Ok(Command::Unsnapshot(0, "3".to_string())));
+		assert!(parse("snapshot").is_err());
+		assert!(parse("rollback 0 3 4").is_err());
+		assert!(parse("unsnapshot 0").is_err());
+	}
+}
@@ -7,7 +7,11 @@
 
 use serialize::json;
 use serialize::json::Json;
-use std::io::fs::File;
+use std::io::fs::{File, PathExtensions};
+use std::collections::HashMap;
+
+use minion;
+use storage;
 
 
 macro_rules! get(
@@ -21,10 +25,23 @@ macro_rules! get(
 	)
 );
 
+macro_rules! get_or(
+	($tree:ident, $name:expr, $method:ident, $default:expr) => (
+		match $tree.get(&($name.to_string())) {
+			Some(value) => try!(value.$method()
+				.ok_or(format!("Option `{}` is of an incorrect type.", $name))),
+			None => $default,
+		}
+	)
+);
+
 
 /// The default configuration file's contents.
 pub const DEFAULT: &'static str = include_str!("config/default.json");
 
+/// The largest seed for network faults, so it fits in a Java `long`.
+pub const MAX_SEED: u64 = 9223372036854775807;
+
 
 /// A set of configuration options.
 #[derive(Show, Clone)]
@@ -33,9 +50,144 @@ pub struct Config {
 	pub computer_height: u32,
 	pub pocket_width: u32,
 	pub pocket_height: u32,
+	pub turtle_width: u32,
+	pub turtle_height: u32,
 	pub space_limit: u64,
 	pub border_width: u32,
 	pub border_height: u32,
+	pub live_audio: bool,
+	pub modem_range: f64,
+	pub modem_max_range: f64,
+	pub computers: HashMap<u32, ComputerConfig>,
+	pub cable_peripherals: HashMap<String, Vec<String>>,
+	pub bridges: Vec<(String, String)>,
+	pub wires: Vec<Wire>,
+	pub adjacent: Vec<Adjacency>,
+	pub link_port: u16,
+	pub link_peers: Vec<String>,
+	pub modem_log: bool,
+	pub modem_log_console: bool,
+	pub control_port: u16,
+	pub world: String,
+	pub turtles_need_fuel: bool,
+	pub pocket_upgrade: String,
+	pub pocket_upgrades: Vec<String>,
+	pub mounts: Vec<Mount>,
+	pub computer_folder: String,
+	pub workspace: String,
+	pub computercraft: String,
+	pub computercraft_jars: HashMap<String, String>,
+	pub workspace_computercraft: HashMap<String, String>,
+	pub computercraft_override: Option<String>,
+	pub faults: HashMap<String, FaultConfig>,
+	pub commands: HashMap<String, CommandResult>,
+}
+
+
+/// Configuration options for a single computer, set under its ID in the
+/// `computers` option.
+#[derive(Show, Clone)]
+pub struct ComputerConfig {
+	/// Pairs of side and cable name, for wired modems attached on startup.
+	pub cables: Vec<(String, String)>,
+
+	/// The computer's x, y, z position in the world.
+	pub position: Option<(f64, f64, f64)>,
+
+	/// The network used by the computer's wireless modems.
+	pub network: Option<String>,
+
+	/// The directory holding the computer's files, overriding its folder in
+	/// the `computer folder` option.
+	pub folder: Option<String>,
+
+	/// The fuel level a turtle starts with.
+	pub fuel: Option<u32>,
+
+	/// Items a turtle starts with, as slot, item name and count.
+	pub inventory: Vec<(u32, String, u32)>,
+
+	/// The upgrade a pocket computer starts with on its back, overriding the
+	/// `pocket upgrade` option.
+	pub upgrade: Option<String>,
+
+	/// A pocket computer's spare upgrades, overriding the `pocket upgrades`
+	/// option.
+	pub upgrades: Option<Vec<String>>,
+
+	/// Host directories mounted in the computer, as well as those in the
+	/// `mounts` option.
+	pub mounts: Vec<Mount>,
+
+	/// Directories layered over the computer's ROM, in order, on top of the
+	/// global and workspace ROM folders.
+	pub rom: Vec<String>,
+
+	/// Whether the `rom` folder in the Mimic data folder is layered over the
+	/// computer's ROM.
+	pub global_rom: bool,
+}
+
+
+/// A directory on the host mounted at a path in a computer's files.
+#[derive(Show, Clone, PartialEq)]
+pub struct Mount {
+	/// The path inside the computer, such as `/lib`.
+	pub path: String,
+
+	/// The directory on the host, relative to the Mimic data folder.
+	pub directory: String,
+
+	pub read_only: bool,
+}
+
+
+/// Unreliable delivery applied to the messages on a wireless network, set
+/// under the network's name in the `faults` option.
+#[derive(Show, Clone, PartialEq)]
+pub struct FaultConfig {
+	/// The number of ticks every message is delayed by.
+	pub delay: u32,
+
+	/// The probability each message is lost, from 0 to 1.
+	pub drop: f64,
+
+	/// The probability each message is held back, so later ones overtake it.
+	pub reorder: f64,
+
+	/// The seed for random choices, or None to pick one.
+	pub seed: Option<u64>,
+}
+
+
+/// The scripted result of a command run by a command computer, set under the
+/// command or command name in the `commands` option.
+#[derive(Show, Clone, PartialEq)]
+pub struct CommandResult {
+	pub success: bool,
+	pub output: Vec<String>,
+}
+
+
+/// A redstone wire carrying the output from a side of one computer into a
+/// side of another.
+#[derive(Show, Clone, PartialEq)]
+pub struct Wire {
+	pub from: u32,
+	pub from_side: String,
+	pub to: u32,
+	pub to_side: String,
+}
+
+
+/// Two computers placed next to each other, so each can wrap the other as a
+/// `computer` peripheral on the given side.
+#[derive(Show, Clone, PartialEq)]
+pub struct Adjacency {
+	pub first: u32,
+	pub first_side: String,
+	pub second: u32,
+	pub second_side: String,
 }
 
 
@@ -61,15 +213,580 @@ impl Config {
 		let tree = try!(json.as_object()
 			.ok_or("Root JSON object is not a dictionary.".to_string()));
 
-		Ok(Config {
+		let mut config = Config {
 			computer_width: get!(tree, "computer width", as_u64) as u32,
 			computer_height: get!(tree, "computer height", as_u64) as u32,
 			pocket_width: get!(tree, "pocket width", as_u64) as u32,
 			pocket_height: get!(tree, "pocket height", as_u64) as u32,
+			turtle_width: get_or!(tree, "turtle width", as_u64, 39) as u32,
+			turtle_height: get_or!(tree, "turtle height", as_u64, 13) as u32,
 			space_limit: get!(tree, "space limit", as_u64),
 			border_width: get!(tree, "border width", as_u64) as u32,
 			border_height: get!(tree, "border height", as_u64) as u32,
+			live_audio: get_or!(tree, "live audio", as_boolean, false),
+			modem_range: get_or!(tree, "modem range", as_f64, 64.0),
+			modem_max_range: get_or!(tree, "modem max range", as_f64, 384.0),
+			computers: try!(Config::computers_from_json(tree)),
+			cable_peripherals: try!(Config::cable_peripherals_from_json(tree)),
+			bridges: try!(Config::bridges_from_json(tree)),
+			wires: try!(Config::wires_from_json(tree)),
+			adjacent: try!(Config::adjacent_from_json(tree)),
+			link_port: get_or!(tree, "link port", as_u64, 0) as u16,
+			link_peers: try!(Config::link_peers_from_json(tree)),
+			modem_log: get_or!(tree, "modem log", as_boolean, false),
+			modem_log_console: get_or!(tree, "modem log console", as_boolean, false),
+			control_port: get_or!(tree, "control port", as_u64, 0) as u16,
+			world: get_or!(tree, "world", as_string, "world.txt").to_string(),
+			turtles_need_fuel: get_or!(tree, "turtles need fuel", as_boolean, true),
+			pocket_upgrade: get_or!(tree, "pocket upgrade", as_string, "modem").to_string(),
+			pocket_upgrades: match tree.get(&"pocket upgrades".to_string()) {
+				Some(value) => try!(strings_from_json(value,
+					"Option `pocket upgrades` must be a list of strings.")),
+				None => Vec::new(),
+			},
+			mounts: match tree.get(&"mounts".to_string()) {
+				Some(value) => try!(mounts_from_json(value, "the `mounts` option")),
+				None => Vec::new(),
+			},
+			computer_folder: get_or!(tree, "computer folder", as_string, "computers").to_string(),
+			faults: try!(Config::faults_from_json(tree)),
+			commands: try!(Config::commands_from_json(tree)),
+			workspace: get_or!(tree, "workspace", as_string, "").to_string(),
+			computercraft: get_or!(tree, "computercraft", as_string, "").to_string(),
+			computercraft_jars: try!(Config::computercraft_jars_from_json(tree)),
+			workspace_computercraft: match tree.get(&"workspace computercraft".to_string()) {
+				Some(value) => try!(string_map_from_json(value,
+					"Option `workspace computercraft` must be a dictionary of strings.")),
+				None => HashMap::new(),
+			},
+			computercraft_override: None,
+		};
+
+		try!(config.check_computercraft(config.computercraft.as_slice()));
+		for name in config.workspace_computercraft.values() {
+			try!(config.check_computercraft(name.as_slice()));
+		}
+
+		Ok(config)
+	}
+
+	/// Returns an error if no ComputerCraft jar is registered with a name in
+	/// the `computercraft jars` option, or if its file doesn't exist. The
+	/// empty name refers to the bundled jar.
+	fn check_computercraft(&self, name: &str) -> Result<(), String> {
+		if name.len() == 0 {
+			return Ok(());
+		}
+
+		match self.computercraft_jars.get(name) {
+			Some(path) => {
+				let file = storage::storage().join(path.as_slice());
+				if !file.exists() {
+					return Err(format!("ComputerCraft jar `{}` not found at `{}`.",
+						name, file.display()));
+				}
+			},
+			None => {
+				let mut names: Vec<&str> = self.computercraft_jars.keys()
+					.map(|name| name.as_slice()).collect();
+				names.sort();
+				return Err(format!("No ComputerCraft jar named `{}`. Expected one of: {}.",
+					name, names.connect(", ")));
+			},
+		}
+
+		Ok(())
+	}
+
+	/// Choose the ComputerCraft jar to use from those registered in the
+	/// `computercraft jars` option, or the bundled one if the name is empty,
+	/// over the one chosen by the workspace or the `computercraft` option.
+	pub fn select_computercraft(&mut self, name: &str) -> Result<(), String> {
+		try!(self.check_computercraft(name));
+		self.computercraft_override = Some(name.to_string());
+		Ok(())
+	}
+
+	/// Returns the name of the ComputerCraft jar in use: the one selected on
+	/// the command line, otherwise the one registered for the workspace in
+	/// `workspace computercraft`, otherwise the `computercraft` option.
+	pub fn computercraft_name(&self) -> &str {
+		if let Some(ref name) = self.computercraft_override {
+			return name.as_slice();
+		}
+
+		if self.workspace.len() > 0 {
+			let workspace = storage::storage().join(self.workspace.as_slice());
+			for (folder, name) in self.workspace_computercraft.iter() {
+				if storage::storage().join(folder.as_slice()) == workspace {
+					return name.as_slice();
+				}
+			}
+		}
+
+		self.computercraft.as_slice()
+	}
+
+	/// Returns the path to the chosen ComputerCraft jar, or None to use the
+	/// bundled one.
+	pub fn computercraft_jar(&self) -> Option<Path> {
+		self.computercraft_jars.get(self.computercraft_name()).map(|path| {
+			storage::storage().join(path.as_slice())
 		})
 	}
 
+	/// Load the ComputerCraft jars that can be chosen between, as paths keyed
+	/// by name.
+	fn computercraft_jars_from_json(tree: &json::Object)
+			-> Result<HashMap<String, String>, String> {
+		let mut jars = HashMap::new();
+		let object = get_or!(tree, "computercraft jars", as_object, return Ok(jars));
+
+		for (name, value) in object.iter() {
+			let path = try!(value.as_string()
+				.ok_or(format!("Path of ComputerCraft jar `{}` is not a string.", name)));
+			jars.insert(name.clone(), path.to_string());
+		}
+
+		Ok(jars)
+	}
+
+	/// Load the addresses of other Mimic processes to link to.
+	fn link_peers_from_json(tree: &json::Object) -> Result<Vec<String>, String> {
+		let mut peers = Vec::new();
+		let list = get_or!(tree, "link peers", as_array, return Ok(peers));
+
+		for item in list.iter() {
+			let address = try!(item.as_string()
+				.ok_or("Option `link peers` must be a list of strings.".to_string()));
+			peers.push(address.to_string());
+		}
+
+		Ok(peers)
+	}
+
+	/// Load the faults applied to each wireless network, keyed by network name.
+	fn faults_from_json(tree: &json::Object) -> Result<HashMap<String, FaultConfig>, String> {
+		let mut faults = HashMap::new();
+		let object = get_or!(tree, "faults", as_object, return Ok(faults));
+
+		for (name, value) in object.iter() {
+			let settings = try!(value.as_object()
+				.ok_or(format!("Faults for network `{}` are not a dictionary.", name)));
+
+			faults.insert(name.clone(), FaultConfig {
+				delay: get_or!(settings, "delay", as_u64, 0) as u32,
+				drop: get_or!(settings, "drop", as_f64, 0.0),
+				reorder: get_or!(settings, "reorder", as_f64, 0.0),
+				seed: match settings.get(&"seed".to_string()) {
+					Some(seed) => match seed.as_u64() {
+						Some(seed) if seed <= MAX_SEED => Some(seed),
+						_ => return Err(format!("Seed for network `{}` is not a number from 0 to {}.",
+							name, MAX_SEED)),
+					},
+					None => None,
+				},
+			});
+		}
+
+		Ok(faults)
+	}
+
+	/// Load the scripted results of commands run by command computers, keyed
+	/// by command.
+	fn commands_from_json(tree: &json::Object)
+			-> Result<HashMap<String, CommandResult>, String> {
+		let mut commands = HashMap::new();
+		let object = get_or!(tree, "commands", as_object, return Ok(commands));
+
+		for (command, value) in object.iter() {
+			let settings = try!(value.as_object()
+				.ok_or(format!("Result for command `{}` is not a dictionary.", command)));
+
+			let mut output = Vec::new();
+			if let Some(lines) = settings.get(&"output".to_string()) {
+				let list = try!(lines.as_array()
+					.ok_or(format!("Output for command `{}` is not a list.", command)));
+
+				for line in list.iter() {
+					let text = try!(line.as_string()
+						.ok_or(format!("Output for command `{}` is not strings.", command)));
+					output.push(text.to_string());
+				}
+			}
+
+			commands.insert(command.clone(), CommandResult {
+				success: get_or!(settings, "success", as_boolean, true),
+				output: output,
+			});
+		}
+
+		Ok(commands)
+	}
+
+	/// Load the redstone wires connecting computers on startup.
+	fn wires_from_json(tree: &json::Object) -> Result<Vec<Wire>, String> {
+		let pairs = try!(Config::side_pairs_from_json(tree, "wires", "wire"));
+		Ok(pairs.into_iter().map(|(from, from_side, to, to_side)| Wire {
+			from: from,
+			from_side: from_side,
+			to: to,
+			to_side: to_side,
+		}).collect())
+	}
+
+	/// Load the pairs of computers placed next to each other on startup.
+	fn adjacent_from_json(tree: &json::Object) -> Result<Vec<Adjacency>, String> {
+		let pairs = try!(Config::side_pairs_from_json(tree, "adjacent", "adjacency"));
+		Ok(pairs.into_iter().map(|(first, first_side, second, second_side)| Adjacency {
+			first: first,
+			first_side: first_side,
+			second: second,
+			second_side: second_side,
+		}).collect())
+	}
+
+	/// Load an option listing pairs of sides of computers, each a list of
+	/// computer ID, side, computer ID, side.
+	fn side_pairs_from_json(tree: &json::Object, option: &str, item_name: &str)
+			-> Result<Vec<(u32, String, u32, String)>, String> {
+		let mut pairs = Vec::new();
+		let list = get_or!(tree, option, as_array, return Ok(pairs));
+		let usage = format!("Each {} must be a list of computer ID, side, computer ID, side.",
+			item_name);
+
+		for item in list.iter() {
+			let parts = try!(item.as_array().ok_or(usage.clone()));
+			if parts.len() != 4 {
+				return Err(usage.clone());
+			}
+
+			let from = try!(parts[0].as_u64().ok_or(usage.clone()));
+			let from_side = try!(parts[1].as_string().ok_or(usage.clone()));
+			let to = try!(parts[2].as_u64().ok_or(usage.clone()));
+			let to_side = try!(parts[3].as_string().ok_or(usage.clone()));
+
+			for side in [from_side, to_side].iter() {
+				if !minion::SIDES.iter().any(|name| name == side) {
+					return Err(format!("Invalid side `{}` in {}.", side, item_name));
+				}
+			}
+
+			pairs.push((from as u32, from_side.to_string(), to as u32, to_side.to_string()));
+		}
+
+		Ok(pairs)
+	}
+
+	/// Load the pairs of wireless networks bridged together on startup.
+	fn bridges_from_json(tree: &json::Object) -> Result<Vec<(String, String)>, String> {
+		let mut bridges = Vec::new();
+		let list = get_or!(tree, "bridges", as_array, return Ok(bridges));
+
+		for item in list.iter() {
+			let names: Vec<&str> = match item.as_array() {
+				Some(pair) => pair.iter().filter_map(|name| name.as_string()).collect(),
+				None => Vec::new(),
+			};
+
+			if names.len() != 2 {
+				return Err("Each bridge must be a list of two network names.".to_string());
+			}
+
+			bridges.push((names[0].to_string(), names[1].to_string()));
+		}
+
+		Ok(bridges)
+	}
+
+	/// Load the per-computer options, keyed by computer ID.
+	fn computers_from_json(tree: &json::Object) -> Result<HashMap<u32, ComputerConfig>, String> {
+		let mut computers = HashMap::new();
+		let object = get_or!(tree, "computers", as_object, return Ok(computers));
+
+		for (key, value) in object.iter() {
+			let id = try!(key.parse::<u32>()
+				.ok_or(format!("Computer ID `{}` is not a number.", key)));
+			computers.insert(id, try!(ComputerConfig::from_json(key.as_slice(), value)));
+		}
+
+		Ok(computers)
+	}
+
+	/// Load the peripherals placed on each cable network on startup.
+	fn cable_peripherals_from_json(tree: &json::Object)
+			-> Result<HashMap<String, Vec<String>>, String> {
+		let mut cables = HashMap::new();
+		let object = get_or!(tree, "cable peripherals", as_object, return Ok(cables));
+
+		for (name, value) in object.iter() {
+			let list = try!(value.as_array()
+				.ok_or(format!("Peripherals for cable `{}` are not a list.", name)));
+
+			let mut types = Vec::new();
+			for item in list.iter() {
+				let kind = try!(item.as_string()
+					.ok_or(format!("Peripherals for cable `{}` are not strings.", name)));
+				types.push(kind.to_string());
+			}
+
+			cables.insert(name.clone(), types);
+		}
+
+		Ok(cables)
+	}
+
+}
+
+
+impl ComputerConfig {
+
+	/// Load a computer's options from a JSON object.
+	fn from_json(id: &str, json: &Json) -> Result<ComputerConfig, String> {
+		let tree = try!(json.as_object()
+			.ok_or(format!("Options for computer {} are not a dictionary.", id)));
+
+		let mut cables = Vec::new();
+		if let Some(value) = tree.get(&"cables".to_string()) {
+			let object = try!(value.as_object()
+				.ok_or(format!("Cables for computer {} are not a dictionary.", id)));
+
+			for (side, cable) in object.iter() {
+				if !minion::SIDES.iter().any(|name| *name == side.as_slice()) {
+					return Err(format!("Invalid side `{}` for computer {}.", side, id));
+				}
+
+				let name = try!(cable.as_string()
+					.ok_or(format!("Cable on side `{}` of computer {} is not a string.", side, id)));
+				cables.push((side.clone(), name.to_string()));
+			}
+		}
+
+		let mut position = None;
+		if let Some(value) = tree.get(&"position".to_string()) {
+			let coordinates: Vec<f64> = match value.as_array() {
+				Some(list) => list.iter().filter_map(|item| item.as_f64()).collect(),
+				None => Vec::new(),
+			};
+
+			if coordinates.len() != 3 {
+				return Err(format!("Position of computer {} is not a list of 3 numbers.", id));
+			}
+
+			position = Some((coordinates[0], coordinates[1], coordinates[2]));
+		}
+
+		let network = match tree.get(&"network".to_string()) {
+			Some(value) => Some(try!(value.as_string()
+				.ok_or(format!("Network of computer {} is not a string.", id))).to_string()),
+			None => None,
+		};
+
+		let folder = match tree.get(&"folder".to_string()) {
+			Some(value) => Some(try!(value.as_string()
+				.ok_or(format!("Folder of computer {} is not a string.", id))).to_string()),
+			None => None,
+		};
+
+		let fuel = match tree.get(&"fuel".to_string()) {
+			Some(value) => Some(try!(value.as_u64()
+				.ok_or(format!("Fuel of computer {} is not a number.", id))) as u32),
+			None => None,
+		};
+
+		let mut inventory = Vec::new();
+		if let Some(value) = tree.get(&"inventory".to_string()) {
+			let object = try!(value.as_object()
+				.ok_or(format!("Inventory of computer {} is not a dictionary.", id)));
+
+			for (slot, item) in object.iter() {
+				let number = match slot.parse::<u32>() {
+					Some(number) if number >= 1 && number <= 16 => number,
+					_ => return Err(format!("Invalid inventory slot `{}` for computer {}.",
+						slot, id)),
+				};
+
+				let parts = item.as_array().map_or(Vec::new(), |list| list.clone());
+				let name = parts.get(0).and_then(|part| part.as_string());
+				let count = parts.get(1).and_then(|part| part.as_u64());
+				match (name, count, parts.len()) {
+					(Some(name), Some(count), 2) =>
+						inventory.push((number, name.to_string(), count as u32)),
+					_ => return Err(format!(
+						"Slot {} of computer {} is not a list of item name and count.", slot, id)),
+				}
+			}
+		}
+
+		let upgrade = match tree.get(&"upgrade".to_string()) {
+			Some(value) => Some(try!(value.as_string()
+				.ok_or(format!("Upgrade of computer {} is not a string.", id))).to_string()),
+			None => None,
+		};
+
+		let upgrades = match tree.get(&"upgrades".to_string()) {
+			Some(value) => Some(try!(strings_from_json(value,
+				format!("Upgrades of computer {} are not a list of strings.", id).as_slice()))),
+			None => None,
+		};
+
+		let mounts = match tree.get(&"mounts".to_string()) {
+			Some(value) => try!(mounts_from_json(value,
+				format!("computer {}", id).as_slice())),
+			None => Vec::new(),
+		};
+
+		let rom = match tree.get(&"rom".to_string()) {
+			Some(value) => try!(strings_from_json(value,
+				format!("ROM layers of computer {} are not a list of strings.", id).as_slice())),
+			None => Vec::new(),
+		};
+
+		Ok(ComputerConfig {
+			cables: cables,
+			position: position,
+			network: network,
+			folder: folder,
+			fuel: fuel,
+			inventory: inventory,
+			upgrade: upgrade,
+			upgrades: upgrades,
+			mounts: mounts,
+			rom: rom,
+			global_rom: get_or!(tree, "global rom", as_boolean, true),
+		})
+	}
+
+}
+
+
+/// Load a list of strings, returning the given error if it isn't one.
+fn strings_from_json(json: &Json, error: &str) -> Result<Vec<String>, String> {
+	let list = try!(json.as_array().ok_or(error.to_string()));
+
+	let mut strings = Vec::new();
+	for item in list.iter() {
+		strings.push(try!(item.as_string().ok_or(error.to_string())).to_string());
+	}
+
+	Ok(strings)
+}
+
+/// Load a dictionary of strings, returning the given error if it isn't one.
+fn string_map_from_json(json: &Json, error: &str) -> Result<HashMap<String, String>, String> {
+	let object = try!(json.as_object().ok_or(error.to_string()));
+
+	let mut strings = HashMap::new();
+	for (key, value) in object.iter() {
+		let string = try!(value.as_string().ok_or(error.to_string()));
+		strings.insert(key.clone(), string.to_string());
+	}
+
+	Ok(strings)
+}
+
+/// Load a dictionary of directories to mount, keyed by path inside the
+/// computer. Each is either a directory, mounted read only, or a dictionary
+/// with a `directory` and optional `read only`.
+fn mounts_from_json(json: &Json, owner: &str) -> Result<Vec<Mount>, String> {
+	let object = try!(json.as_object()
+		.ok_or(format!("Mounts for {} are not a dictionary.", owner)));
+
+	let mut mounts = Vec::new();
+	for (path, value) in object.iter() {
+		let error = format!("Mount `{}` for {} is not a directory or dictionary.", path, owner);
+		let (directory, read_only) = match value.as_string() {
+			Some(directory) => (directory.to_string(), true),
+			None => {
+				let settings = try!(value.as_object().ok_or(error.clone()));
+				let directory = try!(settings.get(&"directory".to_string())
+					.and_then(|directory| directory.as_string()).ok_or(error.clone()));
+				(directory.to_string(), get_or!(settings, "read only", as_boolean, true))
+			},
+		};
+
+		mounts.push(Mount {
+			path: path.clone(),
+			directory: directory,
+			read_only: read_only,
+		});
+	}
+
+	Ok(mounts)
+}
+
+
+#[cfg(test)]
+mod test {
+	use super::{Config, CommandResult};
+	use serialize::json;
+
+	/// Parse a JSON dictionary.
+	fn tree(source: &str) -> json::Object {
+		json::from_str(source).unwrap().as_object().unwrap().clone()
+	}
+
+	#[test]
+	fn side_pairs() {
+		let pairs = Config::side_pairs_from_json(
+			&tree(r#"{"wires": [[0, "right", 1, "left"], [2, "top", 0, "bottom"]]}"#),
+			"wires", "wire");
+		assert_eq!(pairs, Ok(vec![
+			(0, "right".to_string(), 1, "left".to_string()),
+			(2, "top".to_string(), 0, "bottom".to_string()),
+		]));
+	}
+
+	#[test]
+	fn missing_side_pairs() {
+		assert_eq!(Config::side_pairs_from_json(&tree("{}"), "wires", "wire"), Ok(vec![]));
+	}
+
+	#[test]
+	fn invalid_side_pairs() {
+		for source in [
+			r#"{"wires": [[0, "right", 1]]}"#,
+			r#"{"wires": [[0, "right", 1, "left", 2]]}"#,
+			r#"{"wires": [["0", "right", 1, "left"]]}"#,
+			r#"{"wires": [[0, "right", -1, "left"]]}"#,
+			r#"{"wires": [[0, "sideways", 1, "left"]]}"#,
+			r#"{"wires": [0, "right", 1, "left"]}"#,
+		].iter() {
+			assert!(Config::side_pairs_from_json(&tree(*source), "wires", "wire").is_err());
+		}
+	}
+
+	#[test]
+	fn commands() {
+		let commands = Config::commands_from_json(&tree(r#"{"commands": {
+			"time query daytime": {"output": ["The time is 1000"]},
+			"kill @e": {"success": false},
+			"say hi": {}
+		}}"#)).unwrap();
+
+		assert_eq!(commands.len(), 3);
+		assert_eq!(commands.get("time query daytime"), Some(&CommandResult {
+			success: true,
+			output: vec!["The time is 1000".to_string()],
+		}));
+		assert_eq!(commands.get("kill @e"), Some(&CommandResult {
+			success: false,
+			output: vec![],
+		}));
+		assert_eq!(commands.get("say hi"), Some(&CommandResult {
+			success: true,
+			output: vec![],
+		}));
+	}
+
+	#[test]
+	fn invalid_commands() {
+		for source in [
+			r#"{"commands": {"say hi": true}}"#,
+			r#"{"commands": {"say hi": {"output": "hi"}}}"#,
+			r#"{"commands": {"say hi": {"output": [1]}}}"#,
+		].iter() {
+			assert!(Config::commands_from_json(&tree(*source)).is_err());
+		}
+	}
 }
@@ -0,0 +1,208 @@
+//
+//  Control
+//! Accepts commands from other programs over a local TCP socket.
+//
+//  Each line sent to the socket is a command, in the same form as those typed
+//  into the console. The output of each command is sent back one line at a
+//  time, followed by a line containing only a `.`. Errors start with
+//  `error: `. Messages received on subscribed channels may be sent at any
+//  time between responses, as lines starting with `message`.
+//
+
+
+use std::io::{BufferedReader, Listener, Acceptor};
+use std::io::net::tcp::{TcpListener, TcpStream};
+use std::thread::Thread;
+use std::sync::mpsc::{channel, Sender, Receiver};
+
+
+/// The line sent after the output of each command.
+pub const TERMINATOR: &'static str = ".";
+
+
+/// Where a command came from, and so where its output should go.
+#[derive(Clone)]
+pub enum Client {
+	Console,
+
+	/// Arguments: connection ID, sender for lines of output
+	Control(u32, Sender<String>),
+}
+
+
+impl Client {
+
+	/// Sends a single line of output to the client, returning false if the
+	/// client has disconnected.
+	pub fn send(&self, line: &str) -> bool {
+		match *self {
+			Client::Console => {
+				println!("{}", line);
+				true
+			},
+			Client::Control(_, ref sender) =>
+				sender.send(format!("{}\n", line)).is_ok(),
+		}
+	}
+
+	/// Sends the result of a command to the client.
+	pub fn respond(&self, result: Result<String, String>) -> bool {
+		match *self {
+			Client::Console => {
+				match result {
+					Ok(ref output) if output.len() > 0 => println!("{}", output),
+					Ok(_) => {},
+					Err(ref message) => println!("{}", message),
+				}
+				true
+			},
+			Client::Control(..) => {
+				let text = match result {
+					Ok(output) => output,
+					Err(message) => format!("error: {}", message),
+				};
+
+				for line in text.as_slice().lines() {
+					if !self.send(line) {
+						return false;
+					}
+				}
+
+				self.send(TERMINATOR)
+			},
+		}
+	}
+
+	/// Returns true if both clients are the same.
+	pub fn is(&self, other: &Client) -> bool {
+		match (self, other) {
+			(&Client::Console, &Client::Console) => true,
+			(&Client::Control(a, _), &Client::Control(b, _)) => a == b,
+			_ => false,
+		}
+	}
+
+}
+
+
+/// A line received over the control socket.
+pub struct Request {
+	pub line: String,
+	pub client: Client,
+}
+
+
+/// Listens for connections on the control socket on a background thread.
+pub struct Control {
+	receiver: Receiver<Request>,
+}
+
+
+impl Control {
+
+	/// Start listening on the given port on the local machine.
+	pub fn new(port: u16) -> Control {
+		let (sender, receiver) = channel();
+
+		Thread::spawn(move || {
+			let listener = match TcpListener::bind(("127.0.0.1", port)) {
+				Ok(listener) => listener,
+				Err(err) => {
+					println!("Failed to start control socket on port {}: {}", port, err);
+					return;
+				},
+			};
+
+			let mut acceptor = listener.listen().unwrap();
+			let mut next_id = 0;
+			for stream in acceptor.incoming() {
+				if let Ok(stream) = stream {
+					Control::handle(stream, next_id, sender.clone());
+					next_id += 1;
+				}
+			}
+		});
+
+		Control {
+			receiver: receiver,
+		}
+	}
+
+	/// Read commands from a connection, and write output to it, on two new
+	/// threads.
+	fn handle(stream: TcpStream, id: u32, requests: Sender<Request>) {
+		let (sender, receiver) = channel::<String>();
+		let mut writer = stream.clone();
+
+		Thread::spawn(move || {
+			for text in receiver.iter() {
+				if writer.write_str(text.as_slice()).is_err() {
+					break;
+				}
+			}
+		});
+
+		Thread::spawn(move || {
+			let client = Client::Control(id, sender);
+			let mut reader = BufferedReader::new(stream);
+			for line in reader.lines() {
+				let text = match line {
+					Ok(text) => text.as_slice().trim().to_string(),
+					Err(_) => break,
+				};
+
+				if text.len() > 0 {
+					let request = Request {
+						line: text,
+						client: client.clone(),
+					};
+
+					if requests.send(request).is_err() {
+						break;
+					}
+				}
+			}
+		});
+	}
+
+	/// Returns every request received since the last call, without blocking.
+	pub fn requests(&self) -> Vec<Request> {
+		let mut requests = Vec::new();
+		while let Ok(request) = self.receiver.try_recv() {
+			requests.push(request);
+		}
+		requests
+	}
+
+}
+
+
+/// Send a single command to a running emulator's control socket, printing
+/// its output. For `subscribe`, keeps printing received messages until the
+/// connection closes.
+pub fn send(port: u16, line: &str) -> Result<(), String> {
+	let mut stream = try!(TcpStream::connect(("127.0.0.1", port)).ok()
+		.ok_or(format!("Couldn't connect to Mimic on port {}. Is it running?", port)));
+	try!(stream.write_str(format!("{}\n", line).as_slice()).ok()
+		.ok_or("Failed to send command.".to_string()));
+
+	let follow = line.trim().starts_with("subscribe");
+	let mut reader = BufferedReader::new(stream);
+	for received in reader.lines() {
+		let text = match received {
+			Ok(text) => text,
+			Err(_) => break,
+		};
+
+		let trimmed = text.as_slice().trim_right_matches('\n');
+		if trimmed == TERMINATOR {
+			if !follow {
+				break;
+			}
+		} else {
+			println!("{}", trimmed);
+		}
+	}
+
+	Ok(())
+}
@@ -5,19 +5,51 @@
 //
 
 
-use jni::{JavaVM, Class};
-use config::Config;
-use minion::{Minion, Action, Options};
+use std::cmp;
+use std::collections::HashMap;
+use std::io::fs::PathExtensions;
+
+use jni::{JavaVM, Class, Object, Value, Type};
+use terminal::window::time;
+use config::{Config, FaultConfig, Wire, Adjacency, Mount};
+use minion;
+use minion::{Minion, Action, Options, Kind, SIDES};
+use command;
+use command::{Command, Console};
+use control::{Control, Client};
+use viewer::Viewer;
 
 use storage;
 
 
+/// The distance between each computer in a GPS constellation.
+const GPS_SPACING: f64 = 4.0;
+
+
+/// A client listening for messages on a channel of a wireless network.
+struct Subscription {
+	network: String,
+	channel: u16,
+	client: Client,
+}
+
+
 /// The emulator class binding the JavaVM and terminal display.
 pub struct Emulator {
 	_jvm: JavaVM,
 	java_class: Class,
+	host: Object,
+	console: Console,
+	control: Option<Control>,
+	subscriptions: Vec<Subscription>,
+	wires: Vec<Wire>,
+	wired_inputs: HashMap<(u32, String), (u8, u16)>,
+	redstone_changed: bool,
+	imported_labels: HashMap<u32, String>,
 	minions: Vec<Minion>,
+	viewer: Option<Viewer>,
 	last_id: i32,
+	advance_time: f64,
 	config: Config,
 }
 
@@ -26,40 +58,148 @@ impl Emulator {
 
 	/// Create a new emulator.
 	pub fn new(config: &Config) -> Emulator {
-		let mut jvm = JavaVM::new(storage::classpath().as_slice()).unwrap();
+		let classpath = storage::classpath(config.computercraft_jar());
+		let mut jvm = JavaVM::new(classpath.as_slice()).unwrap();
 		jvm.set_calls_destructor(false);
 		let class = jvm.class("Minion").unwrap();
+		let host = jvm.class("Host").unwrap().instance(&[
+			Value::String(storage::storage().as_str().unwrap().to_string()),
+			Value::Boolean(config.live_audio),
+		]).unwrap();
 
-		Emulator {
+		let mut emulator = Emulator {
 			_jvm: jvm,
 			java_class: class,
+			host: host,
+			console: Console::new(),
+			control: None,
+			subscriptions: Vec::new(),
+			wires: config.wires.clone(),
+			wired_inputs: HashMap::new(),
+			redstone_changed: true,
+			imported_labels: HashMap::new(),
 			minions: Vec::new(),
+			viewer: None,
 			last_id: -1,
+			advance_time: time(),
 			config: config.clone(),
+		};
+
+		let version = emulator.host.call("getComputerCraftVersion", &[], Type::String)
+			.unwrap().to_string();
+		println!("Using ComputerCraft {} from {}.", version, classpath[1].display());
+
+		emulator.host.call("setModemRange", &[
+			Value::Double(config.modem_range),
+			Value::Double(config.modem_max_range),
+		], Type::Void).unwrap();
+
+		// Start recording modem traffic
+		if config.modem_log {
+			emulator.host.call("openModemLog", &[], Type::Void).unwrap();
+		}
+
+		emulator.host.call("setModemLogConsole", &[
+			Value::Boolean(config.modem_log_console),
+		], Type::Void).unwrap();
+
+		// Record and script the commands run by command computers
+		emulator.host.call("openCommandLog", &[], Type::Void).unwrap();
+
+		for (command, result) in config.commands.iter() {
+			emulator.host.call("setCommandResult", &[
+				Value::String(command.clone()),
+				Value::Boolean(result.success),
+				Value::String(result.output.connect("\n")),
+			], Type::Void).unwrap();
 		}
+
+		// Link to other Mimic processes
+		if config.link_port != 0 {
+			emulator.host.call("listenLink", &[
+				Value::Int(config.link_port as i32),
+			], Type::Void).unwrap();
+		}
+
+		for peer in config.link_peers.iter() {
+			let valid = emulator.host.call("connectLink", &[
+				Value::String(peer.clone()),
+			], Type::Boolean).unwrap().to_bool();
+
+			if !valid {
+				println!("Invalid link peer address `{}`, expected `host:port`.", peer);
+			}
+		}
+
+		for &(ref a, ref b) in config.bridges.iter() {
+			emulator.bridge(a.as_slice(), b.as_slice());
+		}
+
+		for (network, faults) in config.faults.iter() {
+			let description = emulator.set_faults(network.as_slice(), faults);
+			println!("Faults on network `{}`: {}.", network, description);
+		}
+
+		// Place peripherals on cable networks
+		for (cable, types) in config.cable_peripherals.iter() {
+			for kind in types.iter() {
+				let result = emulator.attach_cable_peripheral(cable.as_slice(), kind.as_slice());
+				Client::Console.respond(result);
+			}
+		}
+
+		// Load the turtles' world, if there is one
+		let world = storage::storage().join(config.world.as_slice());
+		if world.exists() {
+			let error = emulator.host.call("loadWorld", &[
+				Value::String(world.as_str().unwrap().to_string()),
+			], Type::String).unwrap().to_string();
+
+			if error.len() > 0 {
+				println!("{}", error);
+			}
+		}
+
+		// Accept commands from other programs
+		if config.control_port != 0 {
+			emulator.control = Some(Control::new(config.control_port));
+		}
+
+		emulator
+	}
+
+	/// Create and turn on a new minion with an automatically assigned ID.
+	pub fn new_minion(&mut self, advanced: bool, kind: Kind) -> u32 {
+		let id = self.create_minion(advanced, kind);
+		self.minions[id as usize].turn_on();
+		id
 	}
 
-	/// Create a new minion with an automatically assigned ID.
-	pub fn new_minion(&mut self, advanced: bool, pocket: bool) {
+	/// Create a new minion with an automatically assigned ID, without turning
+	/// it on, so it can be set up before it boots.
+	fn create_minion(&mut self, advanced: bool, kind: Kind) -> u32 {
 		self.last_id += 1;
 
 		// Get the minion's width and height
-		let (width, height) = if pocket {
-			(self.config.pocket_width, self.config.pocket_height)
-		} else {
-			(self.config.computer_width, self.config.computer_height)
+		let (width, height) = match kind {
+			Kind::Computer => (self.config.computer_width, self.config.computer_height),
+			Kind::Pocket => (self.config.pocket_width, self.config.pocket_height),
+			Kind::Turtle => (self.config.turtle_width, self.config.turtle_height),
+			Kind::Command => (self.config.computer_width, self.config.computer_height),
 		};
 
 		// Create its title
-		let title = if pocket {
-			format!("Pocket Computer {}", self.last_id)
-		} else {
-			format!("Computer {}", self.last_id)
+		let title = match kind {
+			Kind::Computer => format!("Computer {}", self.last_id),
+			Kind::Pocket => format!("Pocket Computer {}", self.last_id),
+			Kind::Turtle => format!("Turtle {}", self.last_id),
+			Kind::Command => format!("Command Computer {}", self.last_id),
 		};
 
 		// Create its options
 		let options = Options {
 			id: self.last_id as u32,
+			kind: kind,
 			advanced: advanced,
 			title: title,
 			width: width,
@@ -67,6 +207,7 @@ impl Emulator {
 			space_limit: self.config.space_limit,
 			border_width: self.config.border_width,
 			border_height: self.config.border_height,
+			live_audio: self.config.live_audio,
 		};
 
 		// Create the minion itself
@@ -77,6 +218,652 @@ impl Emulator {
 		};
 
 		self.minions.push(minion);
+		self.redstone_changed = true;
+		if kind == Kind::Turtle {
+			let needs_fuel = self.config.turtles_need_fuel;
+			self.minions[self.last_id as usize].make_turtle(needs_fuel);
+		} else if kind == Kind::Command {
+			self.minions[self.last_id as usize].make_command_computer();
+		} else if kind == Kind::Pocket {
+			let computer = self.config.computers.get(&(self.last_id as u32));
+			let upgrade = computer.and_then(|computer| computer.upgrade.clone())
+				.unwrap_or(self.config.pocket_upgrade.clone());
+			let spares = computer.and_then(|computer| computer.upgrades.clone())
+				.unwrap_or(self.config.pocket_upgrades.clone());
+
+			let result = self.minions[self.last_id as usize]
+				.make_pocket(upgrade.as_slice(), &spares);
+			if let Err(message) = result {
+				println!("{}", message);
+			}
+		}
+
+		let id = self.last_id as u32;
+		let directory = self.save_directory(id);
+		self.minions[id as usize].set_save_directory(directory.as_str().unwrap());
+
+		// Layer the workspace's ROM folder over the global one
+		if self.config.workspace.len() > 0 {
+			let rom = storage::storage().join(self.config.workspace.as_slice()).join("rom");
+			self.minions[id as usize].add_rom_layer(rom.as_str().unwrap());
+		}
+
+		for mount in self.config.mounts.clone().iter() {
+			if let Err(message) = self.mount(id, mount) {
+				println!("{}", message);
+			}
+		}
+
+		self.configure_minion(id);
+
+		// Give it the label of an archive imported before it existed
+		if let Some(label) = self.imported_labels.remove(&id) {
+			self.minions[id as usize].set_label(label.as_slice());
+		}
+
+		// Place it next to any computers it's adjacent to that already exist
+		let adjacent: Vec<Adjacency> = self.config.adjacent.iter().filter(|adjacency| {
+			(adjacency.first == id && adjacency.second <= id) ||
+				(adjacency.second == id && adjacency.first <= id)
+		}).map(|adjacency| adjacency.clone()).collect();
+
+		for adjacency in adjacent.iter() {
+			if let Err(message) = self.place_adjacent(adjacency) {
+				println!("{}", message);
+			}
+		}
+
+		id
+	}
+
+	/// Returns the directory holding a computer's files.
+	fn save_directory(&self, id: u32) -> Path {
+		let folder = self.config.computers.get(&id).and_then(|computer| computer.folder.clone());
+		match folder {
+			Some(folder) => storage::storage().join(folder.as_slice()),
+			None => storage::storage().join(self.config.computer_folder.as_slice())
+				.join(id.to_string().as_slice()),
+		}
+	}
+
+	/// Write a computer's files, label and type to a zip archive, by default
+	/// in the `exports` folder. Returns the archive's path.
+	fn export_computer(&mut self, id: u32, file: Option<String>) -> Result<String, String> {
+		let name = file.unwrap_or(format!("exports/computer_{}.zip", id));
+		let path = storage::storage().join(name.as_slice());
+		let path = path.as_str().unwrap().to_string();
+
+		try!(try!(self.minion(id)).export_archive(path.as_slice()));
+		Ok(path)
+	}
+
+	/// Load an archive's files and label into a computer, rebooting it if it's
+	/// on, or into a new computer of the type in the archive if no ID is
+	/// given. A computer that doesn't exist yet gets the files and label once
+	/// it's created. Existing files are handled as described by the mode.
+	/// Returns the computer's ID.
+	fn import_computer(&mut self, file: &str, id: Option<u32>, mode: &str)
+			-> Result<u32, String> {
+		let path = storage::storage().join(file);
+		let path = path.as_str().unwrap();
+
+		match id {
+			Some(id) if (id as usize) < self.minions.len() => {
+				try!(self.minions[id as usize].import_archive(path, mode));
+				Ok(id)
+			},
+			Some(id) => {
+				// Leave the files and label for when the computer is created
+				let directory = self.save_directory(id);
+				try!(self.extract_archive(path, directory.as_str().unwrap(), mode));
+				let label = self.archive_metadata(path, "label");
+				self.imported_labels.insert(id, label);
+				Ok(id)
+			},
+			None => {
+				let kind = self.archive_metadata(path, "kind");
+				let kind = match Kind::from_name(kind.as_slice()) {
+					Some(kind) => kind,
+					None => return Err(format!("{} is not a computer archive.", file)),
+				};
+				let advanced = self.archive_metadata(path, "advanced").as_slice() != "false";
+				let label = self.archive_metadata(path, "label");
+
+				let directory = self.save_directory((self.last_id + 1) as u32);
+				try!(self.extract_archive(path, directory.as_str().unwrap(), mode));
+
+				let id = self.create_minion(advanced, kind);
+				self.minions[id as usize].set_label(label.as_slice());
+				self.minions[id as usize].turn_on();
+				Ok(id)
+			},
+		}
+	}
+
+	/// Returns a value from an archive's metadata, or an empty string.
+	fn archive_metadata(&self, path: &str, key: &str) -> String {
+		self.host.call("getArchiveMetadata", &[
+			Value::String(path.to_string()),
+			Value::String(key.to_string()),
+		], Type::String).unwrap().to_string()
+	}
+
+	/// Copy a computer's files into a new snapshot.
+	fn take_snapshot(&mut self, id: u32) -> Result<String, String> {
+		let minion = try!(self.minion(id));
+		try!(minion.take_snapshot());
+		let name = minion.latest_snapshot().unwrap_or(String::new());
+		Ok(format!("Took snapshot {} of computer {}.", name, id))
+	}
+
+	/// Restore a computer's files from a snapshot, or its latest one if no
+	/// name is given, rebooting it if it's on.
+	fn rollback(&mut self, id: u32, name: Option<String>) -> Result<String, String> {
+		let minion = try!(self.minion(id));
+		let name = match name.or_else(|| minion.latest_snapshot()) {
+			Some(name) => name,
+			None => return Err(format!("Computer {} has no snapshots.", id)),
+		};
+
+		try!(minion.rollback(name.as_slice()));
+		Ok(format!("Rolled computer {} back to snapshot {}.", id, name))
+	}
+
+	/// Extract the files in an archive into a directory.
+	fn extract_archive(&self, path: &str, directory: &str, mode: &str) -> Result<(), String> {
+		let error = self.host.call("extractArchive", &[
+			Value::String(path.to_string()),
+			Value::String(directory.to_string()),
+			Value::String(mode.to_string()),
+		], Type::String).unwrap().to_string();
+
+		minion::result(error)
+	}
+
+	/// Mount a host directory in a computer, relative to the Mimic data folder
+	/// unless it's absolute.
+	fn mount(&mut self, id: u32, mount: &Mount) -> Result<(), String> {
+		let directory = storage::storage().join(mount.directory.as_slice());
+		try!(self.minion(id)).add_mount(mount.path.as_slice(), directory.as_str().unwrap(),
+			mount.read_only)
+	}
+
+	/// Place two computers next to each other, so each appears as a
+	/// `computer` peripheral on the given side of the other.
+	fn place_adjacent(&mut self, adjacency: &Adjacency) -> Result<(), String> {
+		try!(self.minion(adjacency.first));
+		try!(self.minion(adjacency.second));
+
+		let first_side = adjacency.first_side.as_slice();
+		if !self.minions[adjacency.first as usize]
+				.attach_computer(first_side, adjacency.second) {
+			return Err(format!("No computer with ID {}.", adjacency.second));
+		}
+
+		if !self.minions[adjacency.second as usize]
+				.attach_computer(adjacency.second_side.as_slice(), adjacency.first) {
+			// Don't leave the first computer holding a one sided connection
+			self.minions[adjacency.first as usize].detach_peripheral(first_side);
+			return Err(format!("No computer with ID {}.", adjacency.first));
+		}
+
+		Ok(())
+	}
+
+	/// Apply any options in the configuration file specific to a computer.
+	fn configure_minion(&mut self, id: u32) {
+		let computer = match self.config.computers.get(&id) {
+			Some(computer) => computer.clone(),
+			None => return,
+		};
+
+		for mount in computer.mounts.iter() {
+			if let Err(message) = self.mount(id, mount) {
+				println!("{}", message);
+			}
+		}
+
+		let minion = &mut self.minions[id as usize];
+		minion.set_global_rom(computer.global_rom);
+		for layer in computer.rom.iter() {
+			let directory = storage::storage().join(layer.as_slice());
+			minion.add_rom_layer(directory.as_str().unwrap());
+		}
+
+		if let Some(ref network) = computer.network {
+			minion.set_network(network.as_slice());
+		}
+
+		for &(ref side, ref cable) in computer.cables.iter() {
+			minion.attach_wired_modem(side.as_slice(), cable.as_slice());
+		}
+
+		if let Some((x, y, z)) = computer.position {
+			minion.set_position(x, y, z);
+		}
+
+		if minion.kind == Kind::Turtle {
+			if let Some(fuel) = computer.fuel {
+				minion.set_turtle_fuel(fuel);
+			}
+
+			for &(slot, ref name, count) in computer.inventory.iter() {
+				minion.set_turtle_item(slot, name.as_slice(), count);
+			}
+		}
+	}
+
+	/// Returns the minion with the given ID, or an error if it doesn't exist.
+	fn minion(&mut self, id: u32) -> Result<&mut Minion, String> {
+		self.minions.get_mut(id as usize).ok_or(format!("No computer with ID {}.", id))
+	}
+
+	/// Spawn four GPS hosts around the given position, so that computers in
+	/// range can use `gps.locate`. Returns a description of each host.
+	pub fn new_gps_constellation(&mut self, x: f64, y: f64, z: f64) -> String {
+		let offsets = [
+			(0.0, 0.0, 0.0),
+			(GPS_SPACING, 0.0, 0.0),
+			(0.0, GPS_SPACING, 0.0),
+			(0.0, 0.0, GPS_SPACING),
+		];
+
+		let mut output = Vec::new();
+		for &(dx, dy, dz) in offsets.iter() {
+			let (hx, hy, hz) = (x + dx, y + dy, z + dz);
+			let id = self.create_minion(true, Kind::Computer);
+
+			let minion = &mut self.minions[id as usize];
+			minion.set_position(hx, hy, hz);
+			minion.attach_modem();
+			minion.set_startup(format!("shell.run(\"gps\", \"host\", \"{}\", \"{}\", \"{}\")\n",
+				hx, hy, hz).as_slice());
+			minion.turn_on();
+
+			output.push(format!("Started GPS host {} at {}, {}, {}.", id, hx, hy, hz));
+		}
+
+		output.connect("\n")
+	}
+
+	/// Bridge two wireless networks together.
+	pub fn bridge(&mut self, a: &str, b: &str) {
+		self.host.call("bridge", &[
+			Value::String(a.to_string()),
+			Value::String(b.to_string()),
+		], Type::Void).unwrap();
+	}
+
+	/// Remove the bridge between two wireless networks.
+	pub fn unbridge(&mut self, a: &str, b: &str) {
+		self.host.call("unbridge", &[
+			Value::String(a.to_string()),
+			Value::String(b.to_string()),
+		], Type::Void).unwrap();
+	}
+
+	/// Set the faults applied to messages on a wireless network, returning a
+	/// description of them including the seed used.
+	pub fn set_faults(&mut self, network: &str, faults: &FaultConfig) -> String {
+		// Seeds are checked to fit in a Java long when they're parsed
+		let seed = match faults.seed {
+			Some(seed) => seed as i64,
+			None => -1,
+		};
+
+		self.host.call("setFaults", &[
+			Value::String(network.to_string()),
+			Value::Int(faults.delay as i32),
+			Value::Double(faults.drop),
+			Value::Double(faults.reorder),
+			Value::Long(seed),
+		], Type::String).unwrap().to_string()
+	}
+
+	/// Carry the redstone outputs of computers along each wire into the inputs
+	/// they're connected to. Inputs driven by several wires take the strongest
+	/// signal and every color, and inputs no longer wired are switched off.
+	/// Only runs when an output, a wire or the set of computers changed.
+	fn propagate_redstone(&mut self) {
+		if !self.redstone_changed {
+			return;
+		}
+		self.redstone_changed = false;
+
+		let mut driven: HashMap<(u32, String), (u8, u16)> = HashMap::new();
+		for wire in self.wires.iter() {
+			let (level, colors) = match self.minions.get(wire.from as usize) {
+				Some(minion) => minion.outputs(wire.from_side.as_slice()),
+				None => (0, 0),
+			};
+
+			let key = (wire.to, wire.to_side.clone());
+			let (previous_level, previous_colors) =
+				driven.get(&key).map_or((0, 0), |value| *value);
+			driven.insert(key, (cmp::max(level, previous_level), colors | previous_colors));
+		}
+
+		for (key, value) in self.wired_inputs.iter() {
+			if !driven.contains_key(key) && *value != (0, 0) {
+				driven.insert(key.clone(), (0, 0));
+			}
+		}
+
+		for (key, value) in driven.into_iter() {
+			if self.wired_inputs.get(&key) == Some(&value) {
+				continue;
+			}
+
+			let &(id, ref side) = &key;
+			if let Some(minion) = self.minions.get_mut(id as usize) {
+				let (level, colors) = value;
+				minion.set_redstone_input(side.as_slice(), level);
+				minion.set_bundled_input(side.as_slice(), colors);
+				self.wired_inputs.insert(key.clone(), value);
+			}
+		}
+	}
+
+	/// Place a new peripheral of the given type on a cable network.
+	pub fn attach_cable_peripheral(&mut self, cable: &str, kind: &str)
+			-> Result<String, String> {
+		let name = self.host.call("attachCablePeripheral", &[
+			Value::String(cable.to_string()),
+			Value::String(kind.to_string()),
+		], Type::String).unwrap().to_string();
+
+		if name.len() == 0 {
+			Err(format!("Unsupported cable peripheral type `{}`.", kind))
+		} else {
+			Ok(format!("Attached {} to cable `{}`.", name, cable))
+		}
+	}
+
+	/// Execute a command typed into the console or sent over the control
+	/// socket, returning its output.
+	pub fn execute(&mut self, command: Command, client: &Client) -> Result<String, String> {
+		match command {
+			Command::Cable(id, side, cable) => {
+				try!(self.minion(id)).attach_wired_modem(side.as_slice(), cable.as_slice());
+			},
+			Command::Detach(id, side) => {
+				try!(self.minion(id)).detach_peripheral(side.as_slice());
+			},
+			Command::Attach(cable, kind) =>
+				return self.attach_cable_peripheral(cable.as_slice(), kind.as_slice()),
+			Command::Remove(cable, name) => {
+				self.host.call("detachCablePeripheral", &[
+					Value::String(cable),
+					Value::String(name),
+				], Type::Void).unwrap();
+			},
+			Command::Peripherals(cable) => {
+				let names = self.host.call("getCablePeripherals", &[
+					Value::String(cable),
+				], Type::String).unwrap().to_string();
+				return Ok(names.as_slice().trim_right().to_string());
+			},
+			Command::Position(id, x, y, z) => {
+				try!(self.minion(id)).set_position(x, y, z);
+			},
+			Command::Gps(x, y, z) => return Ok(self.new_gps_constellation(x, y, z)),
+			Command::Network(id, side, network) => {
+				if !try!(self.minion(id)).set_modem_network(side.as_slice(), network.as_slice()) {
+					return Err(format!("No wireless modem on the {} of computer {}.", side, id));
+				}
+			},
+			Command::Bridge(a, b) => self.bridge(a.as_slice(), b.as_slice()),
+			Command::Unbridge(a, b) => self.unbridge(a.as_slice(), b.as_slice()),
+			Command::Sniff => {
+				let enabled = !self.host.call("isModemLogConsole", &[], Type::Boolean)
+					.unwrap().to_bool();
+				self.host.call("setModemLogConsole", &[
+					Value::Boolean(enabled),
+				], Type::Void).unwrap();
+
+				return Ok(if enabled {
+					concat!("Printing modem traffic (time, network, sender, channel, ",
+						"reply channel, message).").to_string()
+				} else {
+					"Stopped printing modem traffic.".to_string()
+				});
+			},
+			Command::Transmit(network, channel, reply, message) => {
+				let error = self.host.call("transmit", &[
+					Value::String(network),
+					Value::Int(channel as i32),
+					Value::Int(reply as i32),
+					Value::String(message),
+				], Type::String).unwrap().to_string();
+
+				if error.len() > 0 {
+					return Err(error);
+				}
+			},
+			Command::Subscribe(network, channel) => {
+				self.subscribe(network.as_slice(), channel, client);
+				return Ok(format!("Subscribed to channel {} on network `{}`.", channel, network));
+			},
+			Command::Unsubscribe(network, channel) => {
+				self.unsubscribe(network.as_slice(), channel, client);
+				return Ok(format!("Unsubscribed from channel {} on network `{}`.", channel,
+					network));
+			},
+			Command::Faults(network, Some(faults)) => {
+				let description = self.set_faults(network.as_slice(), &faults);
+				return Ok(format!("Faults on network `{}`: {}.", network, description));
+			},
+			Command::Faults(network, None) => {
+				let description = self.host.call("getFaults", &[
+					Value::String(network.clone()),
+				], Type::String).unwrap().to_string();
+				return Ok(format!("Faults on network `{}`: {}.", network, description));
+			},
+			Command::Redstone(id, Some((side, level))) => {
+				try!(self.minion(id)).set_redstone_input(side.as_slice(), level);
+			},
+			Command::Redstone(id, None) => {
+				let minion = try!(self.minion(id));
+				let mut lines = vec!["Side     In  Out  Bundled in  Bundled out".to_string()];
+				for side in SIDES.iter() {
+					lines.push(format!("{:<8} {:>2}  {:>3}  {:>10}  {:>11}", side,
+						minion.redstone_input(*side), minion.redstone_output(*side),
+						minion.bundled_input(*side), minion.bundled_output(*side)));
+				}
+
+				return Ok(lines.connect("\n"));
+			},
+			Command::Bundled(id, side, colors) => {
+				try!(self.minion(id)).set_bundled_input(side.as_slice(), colors);
+			},
+			Command::Wire(wire) => {
+				if !self.wires.contains(&wire) {
+					self.wires.push(wire);
+					self.redstone_changed = true;
+				}
+			},
+			Command::Unwire(wire) => {
+				let count = self.wires.len();
+				self.wires.retain(|existing| *existing != wire);
+				if self.wires.len() == count {
+					return Err("No such wire.".to_string());
+				}
+				self.redstone_changed = true;
+			},
+			Command::Adjacent(adjacency) => try!(self.place_adjacent(&adjacency)),
+			Command::Wires => {
+				let lines: Vec<String> = self.wires.iter().map(|wire| {
+					format!("{} {} -> {} {}", wire.from, wire.from_side, wire.to, wire.to_side)
+				}).collect();
+				return Ok(lines.connect("\n"));
+			},
+			Command::Mount(id, mount) => {
+				try!(self.mount(id, &mount));
+				return Ok(format!("Mounted `{}` in computer {} from its next boot.",
+					mount.path, id));
+			},
+			Command::Unmount(id, path) => {
+				if !try!(self.minion(id)).remove_mount(path.as_slice()) {
+					return Err(format!("Nothing mounted at `{}`.", path));
+				}
+			},
+			Command::Mounts(id) => {
+				let mounts = try!(self.minion(id)).mounts();
+				let lines: Vec<String> = mounts.as_slice().lines().map(|line| {
+					line.replace("\t", "  ")
+				}).collect();
+				return Ok(lines.connect("\n"));
+			},
+			Command::Export(id, file) => {
+				let path = try!(self.export_computer(id, file));
+				return Ok(format!("Exported computer {} to {}.", id, path));
+			},
+			Command::Import(file, id, mode) => {
+				let id = try!(self.import_computer(file.as_slice(), id, mode.as_slice()));
+				return Ok(format!("Imported {} into computer {}.", file, id));
+			},
+			Command::Snapshot(id) => {
+				return self.take_snapshot(id);
+			},
+			Command::Snapshots(id) => {
+				let snapshots = try!(self.minion(id)).snapshots();
+				let mut total = 0;
+				let mut lines = Vec::new();
+				for line in snapshots.as_slice().lines() {
+					let parts: Vec<&str> = line.split('\t').collect();
+					let size = parts[2].parse::<u64>().unwrap_or(0);
+					total += size;
+					lines.push(format!("{:>4}  {}  {}", parts[0], parts[1], format_size(size)));
+				}
+
+				if lines.len() == 0 {
+					return Ok(format!("Computer {} has no snapshots.", id));
+				}
+
+				lines.push(format!("Total {}", format_size(total)));
+				return Ok(lines.connect("\n"));
+			},
+			Command::Rollback(id, name) => {
+				return self.rollback(id, name);
+			},
+			Command::Unsnapshot(id, name) => {
+				try!(try!(self.minion(id)).delete_snapshot(name.as_slice()));
+			},
+			Command::View(id) => try!(self.view_world(id)),
+			Command::Help => return Ok(command::HELP.to_string()),
+		}
+
+		Ok(String::new())
+	}
+
+	/// Open the world view window following a turtle, replacing any window
+	/// already open.
+	fn view_world(&mut self, id: u32) -> Result<(), String> {
+		if try!(self.minion(id)).kind != Kind::Turtle {
+			return Err(format!("Computer {} isn't a turtle.", id));
+		}
+
+		let viewer = Viewer::new(&self.minions[0], id, self.config.border_width,
+			self.config.border_height);
+		self.viewer = Some(viewer);
+		Ok(())
+	}
+
+	/// Redraw the world view window, or forget it once it's been closed.
+	fn update_viewer(&mut self) {
+		let closed = match self.viewer {
+			Some(ref mut viewer) => {
+				viewer.update(&self.minions[viewer.turtle as usize]);
+				!viewer.term.is_running()
+			},
+			None => false,
+		};
+
+		if closed {
+			self.viewer = None;
+		}
+	}
+
+	/// Start sending messages received on a channel of a wireless network to
+	/// a client.
+	fn subscribe(&mut self, network: &str, channel: u16, client: &Client) {
+		let exists = self.subscriptions.iter().any(|subscription| {
+			subscription.network.as_slice() == network && subscription.channel == channel &&
+				subscription.client.is(client)
+		});
+
+		if !exists {
+			self.subscriptions.push(Subscription {
+				network: network.to_string(),
+				channel: channel,
+				client: client.clone(),
+			});
+
+			self.host.call("subscribe", &[
+				Value::String(network.to_string()),
+				Value::Int(channel as i32),
+			], Type::Void).unwrap();
+		}
+	}
+
+	/// Stop sending messages received on a channel of a wireless network to a
+	/// client.
+	fn unsubscribe(&mut self, network: &str, channel: u16, client: &Client) {
+		self.subscriptions.retain(|subscription| {
+			!(subscription.network.as_slice() == network && subscription.channel == channel &&
+				subscription.client.is(client))
+		});
+
+		// Only stop listening once no other client wants the channel
+		let remaining = self.subscriptions.iter().any(|subscription| {
+			subscription.network.as_slice() == network && subscription.channel == channel
+		});
+
+		if !remaining {
+			self.host.call("unsubscribe", &[
+				Value::String(network.to_string()),
+				Value::Int(channel as i32),
+			], Type::Void).unwrap();
+		}
+	}
+
+	/// Send messages received on subscribed channels to each subscribed
+	/// client, dropping any clients that have disconnected.
+	fn dispatch_messages(&mut self) {
+		let messages = self.host.call("pollMessages", &[], Type::String).unwrap().to_string();
+		let mut disconnected = Vec::new();
+
+		for line in messages.as_slice().lines() {
+			let fields: Vec<&str> = line.splitn(2, '\t').collect();
+			if fields.len() < 3 {
+				continue;
+			}
+
+			for subscription in self.subscriptions.iter() {
+				if subscription.network.as_slice() == fields[0] &&
+						subscription.channel.to_string().as_slice() == fields[1] {
+					if !subscription.client.send(format!("message\t{}", line).as_slice()) {
+						disconnected.push((subscription.network.clone(), subscription.channel,
+							subscription.client.clone()));
+					}
+				}
+			}
+		}
+
+		for &(ref network, channel, ref client) in disconnected.iter() {
+			self.unsubscribe(network.as_slice(), channel, client);
+		}
+	}
+
+	/// Parse and execute a line of input, sending the output to the client it
+	/// came from.
+	fn execute_line(&mut self, line: &str, client: &Client) {
+		let result = match command::parse(line) {
+			Ok(command) => self.execute(command, client),
+			Err(message) => Err(message),
+		};
+
+		client.respond(result);
 	}
 
 	/// Returns true if any minion is still running.
@@ -94,8 +881,13 @@ impl Emulator {
 			let mut actions = Vec::new();
 
 			// Advance each minion
+			let mut outputs_changed = false;
 			for minion in self.minions.iter_mut() {
 				minion.advance();
+				if minion.take_outputs_changed() {
+					outputs_changed = true;
+				}
+
 				let potential = minion.trigger_events();
 				match potential {
 					Some(action) => actions.push(action),
@@ -103,16 +895,110 @@ impl Emulator {
 				}
 			}
 
+			if outputs_changed {
+				self.redstone_changed = true;
+			}
+
+			let current_time = time();
+			self.host.call("advance", &[
+				Value::Double(current_time - self.advance_time),
+			], Type::Void).unwrap();
+			self.advance_time = current_time;
+
+			// Execute any commands typed into the console or sent over the
+			// control socket
+			for line in self.console.lines().iter() {
+				self.execute_line(line.as_slice(), &Client::Console);
+			}
+
+			let requests = match self.control {
+				Some(ref control) => control.requests(),
+				None => Vec::new(),
+			};
+
+			for request in requests.iter() {
+				self.execute_line(request.line.as_slice(), &request.client);
+			}
+
+			self.dispatch_messages();
+			self.propagate_redstone();
+			self.update_viewer();
+
 			// Handle any returned actions
 			for action in actions.iter() {
 				match action {
-					&Action::NewComputer(advanced) =>
-						self.new_minion(advanced, false),
-					&Action::NewPocketComputer(advanced) =>
-						self.new_minion(advanced, true),
+					&Action::NewComputer(advanced) => {
+						self.new_minion(advanced, Kind::Computer);
+					},
+					&Action::NewPocketComputer(advanced) => {
+						self.new_minion(advanced, Kind::Pocket);
+					},
+					&Action::NewTurtle(advanced) => {
+						self.new_minion(advanced, Kind::Turtle);
+					},
+					&Action::NewCommandComputer => {
+						self.new_minion(true, Kind::Command);
+					},
+					&Action::ViewWorld(id) => {
+						if let Err(message) = self.view_world(id) {
+							println!("{}", message);
+						}
+					},
+					&Action::Snapshot(id) => {
+						match self.take_snapshot(id) {
+							Ok(message) | Err(message) => println!("{}", message),
+						}
+					},
+					&Action::Rollback(id) => {
+						match self.rollback(id, None) {
+							Ok(message) | Err(message) => println!("{}", message),
+						}
+					},
 				}
 			}
 		}
+
+		// Finish writing any audio before exiting
+		for minion in self.minions.iter() {
+			minion.destroy();
+		}
+
+		self.host.call("shutdown", &[], Type::Void).unwrap();
 	}
 
 }
+
+
+/// Returns a size in bytes as a short human readable string, such as `12.5 KB`.
+fn format_size(bytes: u64) -> String {
+	let units = ["KB", "MB", "GB"];
+	if bytes < 1024 {
+		return format!("{} B", bytes);
+	}
+
+	let mut size = bytes as f64 / 1024.0;
+	let mut unit = 0;
+	while size >= 1024.0 && unit < units.len() - 1 {
+		size /= 1024.0;
+		unit += 1;
+	}
+
+	format!("{:.1} {}", size, units[unit])
+}
+
+
+#[cfg(test)]
+mod test {
+	use super::format_size;
+
+	#[test]
+	fn sizes() {
+		assert_eq!(format_size(0).as_slice(), "0 B");
+		assert_eq!(format_size(1023).as_slice(), "1023 B");
+		assert_eq!(format_size(1024).as_slice(), "1.0 KB");
+		assert_eq!(format_size(12800).as_slice(), "12.5 KB");
+		assert_eq!(format_size(1048576).as_slice(), "1.0 MB");
+		assert_eq!(format_size(5 * 1073741824).as_slice(), "5.0 GB");
+		assert_eq!(format_size(2048 * 1073741824).as_slice(), "2048.0 GB");
+	}
+}
@@ -0,0 +1,335 @@
+/*
+ * Archive
+ *
+ * Exports a computer's files to a zip archive, along with a metadata file
+ * describing the computer, and imports them again.
+ */
+
+
+import java.io.File;
+import java.io.FileInputStream;
+import java.io.FileOutputStream;
+import java.io.IOException;
+import java.io.InputStream;
+import java.io.OutputStream;
+import java.util.ArrayList;
+import java.util.Enumeration;
+import java.util.List;
+import java.util.Properties;
+import java.util.zip.ZipEntry;
+import java.util.zip.ZipFile;
+import java.util.zip.ZipOutputStream;
+
+
+public class Archive {
+
+	/*
+	 * The name of the metadata file in an archive.
+	 */
+	public static final String METADATA = "computer.properties";
+
+	/*
+	 * The folder in an archive holding the computer's files.
+	 */
+	public static final String FILES = "files/";
+
+	/*
+	 * Ways of handling a computer's existing files when importing. Importing
+	 * fails if there are any files, replaces them all, or merges the archive
+	 * into them, overwriting files with the same name.
+	 */
+	public static final String FAIL = "fail";
+	public static final String REPLACE = "replace";
+	public static final String MERGE = "merge";
+
+	/*
+	 * Writes a computer's files and metadata to a zip archive.
+	 */
+	public static void export(File directory, File archive, Properties metadata)
+			throws IOException {
+		File parent = archive.getAbsoluteFile().getParentFile();
+		if (parent != null) {
+			parent.mkdirs();
+		}
+
+		ZipOutputStream zip = new ZipOutputStream(new FileOutputStream(archive));
+		try {
+			zip.putNextEntry(new ZipEntry(METADATA));
+			metadata.store(zip, "Exported from Mimic");
+			zip.closeEntry();
+
+			zip.putNextEntry(new ZipEntry(FILES));
+			zip.closeEntry();
+
+			if (directory.isDirectory()) {
+				addDirectory(zip, directory, FILES);
+			}
+		} finally {
+			zip.close();
+		}
+	}
+
+	private static void addDirectory(ZipOutputStream zip, File directory, String prefix)
+			throws IOException {
+		File[] children = directory.listFiles();
+		if (children == null) {
+			return;
+		}
+
+		for (File child : children) {
+			String name = prefix + child.getName();
+			if (child.isDirectory()) {
+				zip.putNextEntry(new ZipEntry(name + "/"));
+				zip.closeEntry();
+				addDirectory(zip, child, name + "/");
+			} else {
+				zip.putNextEntry(new ZipEntry(name));
+				InputStream input = new FileInputStream(child);
+				try {
+					copy(input, zip);
+				} finally {
+					input.close();
+				}
+
+				zip.closeEntry();
+			}
+		}
+	}
+
+	/*
+	 * Reads the metadata from an archive.
+	 */
+	public static Properties readMetadata(File archive) throws IOException {
+		Properties metadata = new Properties();
+		ZipFile zip = new ZipFile(archive);
+		try {
+			ZipEntry entry = zip.getEntry(METADATA);
+			if (entry == null) {
+				throw new IOException("Not a Mimic computer archive");
+			}
+
+			InputStream input = zip.getInputStream(entry);
+			try {
+				metadata.load(input);
+			} finally {
+				input.close();
+			}
+		} finally {
+			zip.close();
+		}
+
+		return metadata;
+	}
+
+	/*
+	 * Returns true if a directory doesn't exist or has nothing in it.
+	 */
+	public static boolean isEmpty(File directory) {
+		String[] children = directory.list();
+		return children == null || children.length == 0;
+	}
+
+	/*
+	 * Extracts the files in an archive into a computer's directory, handling
+	 * existing files as described by the mode. Every entry is checked before
+	 * anything is written, and the files are extracted into a folder next to
+	 * the computer's, which only replaces it once everything was extracted,
+	 * so a bad archive leaves the computer's files as they were.
+	 */
+	public static void extract(File archive, File directory, String mode) throws IOException {
+		if (!mode.equals(FAIL) && !mode.equals(REPLACE) && !mode.equals(MERGE)) {
+			throw new IOException("Unknown import mode `" + mode + "`");
+		} else if (mode.equals(FAIL) && !isEmpty(directory)) {
+			throw new IOException("Computer already has files in " + directory.getPath() +
+				", import with `replace` or `merge`");
+		}
+
+		ZipFile zip = new ZipFile(archive);
+		File staging = new File(directory.getPath() + ".import");
+		try {
+			if (zip.getEntry(METADATA) == null) {
+				throw new IOException("Not a Mimic computer archive");
+			}
+
+			List<ZipEntry> entries = files(zip, directory);
+
+			deleteTree(staging);
+			if (!staging.mkdirs()) {
+				throw new IOException("Failed to create " + staging.getPath());
+			}
+
+			if (mode.equals(MERGE) && directory.isDirectory()) {
+				copyContents(directory, staging);
+			}
+
+			for (ZipEntry entry : entries) {
+				File file = new File(staging, entry.getName().substring(FILES.length()));
+				if (entry.isDirectory()) {
+					file.mkdirs();
+					continue;
+				}
+
+				file.getParentFile().mkdirs();
+				InputStream input = zip.getInputStream(entry);
+				OutputStream output = new FileOutputStream(file);
+				try {
+					copy(input, output);
+				} finally {
+					input.close();
+					output.close();
+				}
+			}
+
+			replace(directory, staging);
+		} catch (IOException e) {
+			deleteTree(staging);
+			throw e;
+		} finally {
+			zip.close();
+		}
+	}
+
+	/*
+	 * Returns the entries in an archive holding the computer's files,
+	 * throwing if any of them would be extracted outside the directory.
+	 */
+	private static List<ZipEntry> files(ZipFile zip, File directory) throws IOException {
+		String root = directory.getCanonicalPath() + File.separator;
+		List<ZipEntry> files = new ArrayList<ZipEntry>();
+
+		Enumeration<? extends ZipEntry> entries = zip.entries();
+		while (entries.hasMoreElements()) {
+			ZipEntry entry = entries.nextElement();
+			if (!entry.getName().startsWith(FILES) || entry.getName().equals(FILES)) {
+				continue;
+			}
+
+			File file = new File(directory, entry.getName().substring(FILES.length()));
+			if (!file.getCanonicalPath().startsWith(root)) {
+				throw new IOException("Archive entry `" + entry.getName() +
+					"` is outside the computer's files");
+			}
+
+			files.add(entry);
+		}
+
+		return files;
+	}
+
+	/*
+	 * Replaces a directory with a fully prepared one, keeping the old one
+	 * until the new one is in place. A directory that's a link keeps pointing
+	 * to the same place, with its contents replaced instead.
+	 */
+	static void replace(File directory, File staging) throws IOException {
+		if (directory.exists() && isLink(directory)) {
+			deleteContents(directory);
+			copyContents(staging, directory);
+			deleteTree(staging);
+			return;
+		}
+
+		File old = new File(directory.getPath() + ".old");
+		deleteTree(old);
+		if (directory.exists() && !directory.renameTo(old)) {
+			throw new IOException("Failed to move " + directory.getPath());
+		}
+
+		if (!staging.renameTo(directory)) {
+			old.renameTo(directory);
+			throw new IOException("Failed to move " + staging.getPath() + " to " +
+				directory.getPath());
+		}
+
+		// The new files are in place, but say so if the old ones were left
+		// behind
+		try {
+			deleteTree(old);
+		} catch (IOException e) {
+			throw new IOException("Replaced " + directory.getPath() +
+				", but left its old files in " + old.getPath() + ": " + e.getMessage());
+		}
+	}
+
+	/*
+	 * Returns true if a file is a link. Only the file itself is checked, so
+	 * links among the folders it's in don't count.
+	 */
+	static boolean isLink(File file) throws IOException {
+		File parent = file.getAbsoluteFile().getParentFile();
+		return parent != null && !file.getCanonicalFile()
+			.equals(new File(parent.getCanonicalFile(), file.getName()));
+	}
+
+	/*
+	 * Deletes everything inside a directory, leaving the directory itself.
+	 */
+	static void deleteContents(File directory) throws IOException {
+		File[] children = directory.listFiles();
+		if (children == null) {
+			return;
+		}
+
+		for (File child : children) {
+			// Only follow real directories, not links to them
+			if (child.isDirectory() && !isLink(child)) {
+				deleteContents(child);
+			}
+
+			if (!child.delete()) {
+				throw new IOException("Failed to delete " + child.getPath());
+			}
+		}
+	}
+
+	/*
+	 * Deletes a directory and everything inside it, if it exists.
+	 */
+	static void deleteTree(File directory) throws IOException {
+		if (!directory.exists()) {
+			return;
+		}
+
+		deleteContents(directory);
+		if (!directory.delete()) {
+			throw new IOException("Failed to delete " + directory.getPath());
+		}
+	}
+
+	/*
+	 * Copies everything inside a directory into another.
+	 */
+	static void copyContents(File from, File to) throws IOException {
+		File[] children = from.listFiles();
+		if (children == null) {
+			return;
+		}
+
+		for (File child : children) {
+			File target = new File(to, child.getName());
+			if (child.isDirectory()) {
+				target.mkdirs();
+				copyContents(child, target);
+				continue;
+			}
+
+			InputStream input = new FileInputStream(child);
+			OutputStream output = new FileOutputStream(target);
+			try {
+				copy(input, output);
+			} finally {
+				input.close();
+				output.close();
+			}
+		}
+	}
+
+	static void copy(InputStream input, OutputStream output) throws IOException {
+		byte[] buffer = new byte[8192];
+		int read;
+		while ((read = input.read(buffer)) != -1) {
+			output.write(buffer, 0, read);
+		}
+	}
+
+}
@@ -0,0 +1,148 @@
+/*
+ * Cable
+ */
+
+
+import java.util.Map;
+import java.util.HashMap;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.ArrayList;
+
+import dan200.computercraft.api.peripheral.IPeripheral;
+
+
+public class Cable {
+
+	private static Map<String, Cable> cables = new HashMap<String, Cable>();
+
+	private String name;
+	private List<Modem> modems;
+	private Map<String, IPeripheral> peripherals;
+	private Map<String, Integer> counts;
+
+	/*
+	 * Create a new, empty cable network.
+	 */
+	private Cable(String name) {
+		this.name = name;
+		this.modems = new ArrayList<Modem>();
+		this.peripherals = new LinkedHashMap<String, IPeripheral>();
+		this.counts = new HashMap<String, Integer>();
+	}
+
+	/*
+	 * Returns the cable network with the given name, creating it if needed.
+	 */
+	public static synchronized Cable get(String name) {
+		Cable cable = cables.get(name);
+		if (cable == null) {
+			cable = new Cable(name);
+			cables.put(name, cable);
+		}
+
+		return cable;
+	}
+
+	/*
+	 * Returns the name of the cable network.
+	 */
+	public String getName() {
+		return this.name;
+	}
+
+	/*
+	 * Returns the next free peripheral name for the given peripheral type.
+	 */
+	public synchronized String nextName(String type) {
+		Integer count = this.counts.get(type);
+		int index = count == null ? 0 : count.intValue();
+		this.counts.put(type, new Integer(index + 1));
+		return type + "_" + index;
+	}
+
+	/*
+	 * Connects a wired modem to the cable, attaching every peripheral already
+	 * on the network to it.
+	 */
+	public synchronized void connect(Modem modem) {
+		if (!this.modems.contains(modem)) {
+			this.modems.add(modem);
+			for (Map.Entry<String, IPeripheral> entry : this.peripherals.entrySet()) {
+				modem.attachRemote(entry.getKey(), entry.getValue());
+			}
+		}
+	}
+
+	/*
+	 * Disconnects a wired modem from the cable.
+	 */
+	public synchronized void disconnect(Modem modem) {
+		if (this.modems.remove(modem)) {
+			for (String name : this.peripherals.keySet()) {
+				modem.detachRemote(name);
+			}
+		}
+	}
+
+	/*
+	 * Places a peripheral on the network under the given name, making it
+	 * available to every connected modem.
+	 */
+	public synchronized void attachPeripheral(String name, IPeripheral peripheral) {
+		this.detachPeripheral(name);
+		this.peripherals.put(name, peripheral);
+		for (Modem modem : this.modems) {
+			modem.attachRemote(name, peripheral);
+		}
+	}
+
+	/*
+	 * Removes a peripheral from the network, returning it, or null if there
+	 * was no peripheral with that name.
+	 */
+	public synchronized IPeripheral detachPeripheral(String name) {
+		IPeripheral peripheral = this.peripherals.remove(name);
+		if (peripheral != null) {
+			for (Modem modem : this.modems) {
+				modem.detachRemote(name);
+			}
+		}
+
+		return peripheral;
+	}
+
+	/*
+	 * Returns the peripheral on the network with the given name, or null.
+	 */
+	public synchronized IPeripheral getPeripheral(String name) {
+		return this.peripherals.get(name);
+	}
+
+	/*
+	 * Returns the names of every peripheral on the network.
+	 */
+	public synchronized List<String> getPeripheralNames() {
+		return new ArrayList<String>(this.peripherals.keySet());
+	}
+
+	/*
+	 * Transmits a message to every other modem on the cable.
+	 */
+	public void transmit(Modem sender, Object message, int channel, int replyChannel) {
+		Sniffer.record("cable:" + this.name, sender, message, channel, replyChannel);
+
+		List<Modem> receivers;
+		synchronized (this) {
+			receivers = new ArrayList<Modem>(this.modems);
+		}
+
+		for (Modem modem : receivers) {
+			if (modem != sender) {
+				modem.receive(Serializer.copy(message), channel, replyChannel,
+					Network.distance(sender, modem));
+			}
+		}
+	}
+
+}
@@ -0,0 +1,23 @@
+/*
+ * Command Handler
+ *
+ * Runs the Minecraft commands issued by command computers. There's no server
+ * to run them against, so the emulator plugs in a handler deciding what each
+ * command does.
+ */
+
+
+public interface CommandHandler {
+
+	/*
+	 * Runs a command issued by the computer with the given ID, without the
+	 * leading slash, returning whether it succeeded and its output.
+	 */
+	public CommandResult execute(int computer, String command);
+
+	/*
+	 * Returns the names of the commands available.
+	 */
+	public String[] list();
+
+}
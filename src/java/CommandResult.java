@@ -0,0 +1,50 @@
+/*
+ * Command Result
+ *
+ * The outcome of running a command, as returned by a command handler.
+ */
+
+
+import java.util.Map;
+import java.util.HashMap;
+
+
+public class CommandResult {
+
+	public boolean success;
+	public String[] output;
+
+	public CommandResult(boolean success, String[] output) {
+		this.success = success;
+		this.output = output;
+	}
+
+	/*
+	 * Returns the output as a Lua table of lines.
+	 */
+	public Map<Object, Object> getOutputTable() {
+		Map<Object, Object> table = new HashMap<Object, Object>();
+		for (int i = 0; i < this.output.length; i++) {
+			table.put(new Integer(i + 1), this.output[i]);
+		}
+
+		return table;
+	}
+
+	/*
+	 * Returns the output as a single line, for logs.
+	 */
+	public String getOutputLine() {
+		StringBuilder builder = new StringBuilder();
+		for (int i = 0; i < this.output.length; i++) {
+			if (i > 0) {
+				builder.append(" | ");
+			}
+
+			builder.append(this.output[i]);
+		}
+
+		return builder.toString();
+	}
+
+}
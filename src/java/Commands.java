@@ -0,0 +1,222 @@
+/*
+ * Commands
+ *
+ * The `commands` API of command computers. Commands are run by the shared
+ * command handler rather than a Minecraft server, and every command issued is
+ * recorded in the command log.
+ */
+
+
+import java.io.File;
+import java.io.FileWriter;
+import java.io.IOException;
+import java.io.Writer;
+import java.text.SimpleDateFormat;
+import java.util.Date;
+import java.util.Map;
+import java.util.HashMap;
+
+import dan200.computercraft.api.lua.ILuaContext;
+import dan200.computercraft.api.lua.LuaException;
+import dan200.computercraft.core.apis.ILuaAPI;
+
+
+public class Commands implements ILuaAPI {
+
+	private static final String[] METHODS = new String[] {
+		"exec", "execAsync", "list", "getBlockPosition", "getBlockInfo",
+	};
+
+	private static CommandHandler handler = new ScriptedCommandHandler();
+	private static Writer log = null;
+	private static SimpleDateFormat format = new SimpleDateFormat("yyyy-MM-dd HH:mm:ss.SSS");
+
+	private Minion computer;
+	private int taskID;
+
+	/*
+	 * Create the commands API for a computer.
+	 */
+	public Commands(Minion computer) {
+		this.computer = computer;
+		this.taskID = 0;
+	}
+
+
+	/*
+	 * Handler
+	 */
+
+	/*
+	 * Sets the handler running every command computer's commands.
+	 */
+	public static synchronized void setHandler(CommandHandler newHandler) {
+		handler = newHandler;
+	}
+
+	public static synchronized CommandHandler getHandler() {
+		return handler;
+	}
+
+	/*
+	 * Starts appending every issued command to the log file at the given path.
+	 */
+	public static synchronized void openLog(String path) {
+		closeLog();
+
+		try {
+			File file = new File(path);
+			file.getParentFile().mkdirs();
+			log = new FileWriter(file, true);
+			log.write("# Started " + format.format(new Date()) + "\n");
+			log.write("# time\tcomputer\tcommand\tsuccess\toutput\n");
+			log.flush();
+		} catch (IOException e) {
+			System.out.println("Failed to open command log " + path + ": " + e.getMessage());
+			log = null;
+		}
+	}
+
+	/*
+	 * Stops writing to the log file.
+	 */
+	public static synchronized void closeLog() {
+		if (log != null) {
+			try {
+				log.close();
+			} catch (IOException e) {
+				// Nothing else we can do
+			}
+
+			log = null;
+		}
+	}
+
+	/*
+	 * Runs a command with the current handler and records it in the log.
+	 */
+	private static synchronized CommandResult run(int computer, String command) {
+		CommandResult result = handler.execute(computer, command);
+
+		if (log != null) {
+			try {
+				log.write(format.format(new Date()) + "\t" + computer + "\t" + command + "\t" +
+					result.success + "\t" + result.getOutputLine() + "\n");
+				log.flush();
+			} catch (IOException e) {
+				System.out.println("Failed to write to command log: " + e.getMessage());
+				closeLog();
+			}
+		}
+
+		return result;
+	}
+
+
+	/*
+	 * Lua API
+	 */
+
+	private static String parseCommand(Object[] arguments) throws LuaException {
+		if (arguments.length < 1 || !(arguments[0] instanceof String)) {
+			throw new LuaException("Expected string");
+		}
+
+		return (String) arguments[0];
+	}
+
+	private static int parseCoordinate(Object[] arguments, int index) throws LuaException {
+		if (arguments.length <= index || !(arguments[index] instanceof Number)) {
+			throw new LuaException("Expected number, number, number");
+		}
+
+		return (int) Math.floor(((Number) arguments[index]).doubleValue());
+	}
+
+	@Override
+	public String[] getNames() {
+		return new String[] {"commands"};
+	}
+
+	@Override
+	public void startup() {
+	}
+
+	@Override
+	public void advance(double delta) {
+	}
+
+	@Override
+	public void shutdown() {
+	}
+
+	@Override
+	public String[] getMethodNames() {
+		return METHODS;
+	}
+
+	@Override
+	public Object[] callMethod(ILuaContext context, int method, Object[] arguments)
+			throws LuaException, InterruptedException {
+		switch (method) {
+		case 0: {
+			// Exec
+			CommandResult result = run(this.computer.id, parseCommand(arguments));
+			return new Object[] {Boolean.valueOf(result.success), result.getOutputTable()};
+		}
+		case 1: {
+			// Exec async, completing straight away as the handler doesn't block
+			String command = parseCommand(arguments);
+			int id;
+			synchronized (this) {
+				this.taskID++;
+				id = this.taskID;
+			}
+
+			CommandResult result = run(this.computer.id, command);
+			this.computer.queueEvent("task_complete", new Object[] {
+				new Integer(id),
+				Boolean.TRUE,
+				Boolean.valueOf(result.success),
+				result.getOutputTable(),
+			});
+
+			return new Object[] {new Integer(id)};
+		}
+		case 2: {
+			// List
+			String[] names = getHandler().list();
+			Map<Object, Object> table = new HashMap<Object, Object>();
+			for (int i = 0; i < names.length; i++) {
+				table.put(new Integer(i + 1), names[i]);
+			}
+
+			return new Object[] {table};
+		}
+		case 3: {
+			// Get block position
+			double[] position = this.computer.getPosition();
+			return new Object[] {
+				new Integer((int) Math.floor(position[0])),
+				new Integer((int) Math.floor(position[1])),
+				new Integer((int) Math.floor(position[2])),
+			};
+		}
+		case 4: {
+			// Get block info, from the turtles' world
+			int x = parseCoordinate(arguments, 0);
+			int y = parseCoordinate(arguments, 1);
+			int z = parseCoordinate(arguments, 2);
+			String name = World.get().getBlock(x, y, z);
+
+			Map<Object, Object> info = new HashMap<Object, Object>();
+			info.put("name", name == null ? "minecraft:air" : name);
+			info.put("metadata", new Integer(0));
+			return new Object[] {info};
+		}
+		}
+
+		return new Object[] {};
+	}
+
+}
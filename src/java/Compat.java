@@ -0,0 +1,118 @@
+/*
+ * Compat
+ *
+ * Smooths over differences between ComputerCraft versions that otherwise
+ * share the 1.65 API, so Mimic runs against whichever of them is on the
+ * classpath. Anything that changed between them is looked up by reflection
+ * rather than called directly.
+ */
+
+
+import java.io.File;
+import java.io.InputStream;
+import java.io.IOException;
+import java.lang.reflect.Method;
+import java.util.Scanner;
+import java.util.jar.JarFile;
+import java.util.zip.ZipEntry;
+import java.util.regex.Matcher;
+import java.util.regex.Pattern;
+
+import dan200.computercraft.core.computer.Computer;
+import dan200.computercraft.core.terminal.Terminal;
+
+
+public class Compat {
+
+	private static String version = null;
+
+	/*
+	 * Returns the file ComputerCraft's classes were loaded from.
+	 */
+	public static File getJar() {
+		String root = Computer.class.getProtectionDomain().getCodeSource()
+			.getLocation().getPath().replace("%20", " ");
+		if (root.indexOf("!") != -1) {
+			root = root.substring(0, root.indexOf("!"));
+		}
+
+		return new File(root);
+	}
+
+	/*
+	 * Returns the version of ComputerCraft on the classpath, such as `1.65`,
+	 * read from the jar's mod information, or `unknown`.
+	 */
+	public static synchronized String getVersion() {
+		if (version != null) {
+			return version;
+		}
+
+		version = "unknown";
+		try {
+			JarFile jar = new JarFile(getJar());
+			try {
+				ZipEntry entry = jar.getEntry("mcmod.info");
+				if (entry != null) {
+					InputStream stream = jar.getInputStream(entry);
+					String info = new Scanner(stream, "UTF-8").useDelimiter("\\A").next();
+					stream.close();
+
+					Matcher matcher = Pattern.compile("\"version\"\\s*:\\s*\"([^\"]+)\"")
+						.matcher(info);
+					if (matcher.find()) {
+						version = matcher.group(1);
+					}
+				}
+			} finally {
+				jar.close();
+			}
+		} catch (IOException e) {
+			System.out.println("Failed to read the ComputerCraft version: " + e.getMessage());
+		}
+
+		return version;
+	}
+
+	/*
+	 * Calls a method with an int argument on a terminal by name, returning
+	 * its result as a string, or null if the method doesn't exist.
+	 */
+	private static String call(Terminal terminal, String name, int argument) {
+		try {
+			Method method = Terminal.class.getMethod(name, int.class);
+			Object result = method.invoke(terminal, new Integer(argument));
+			return result == null ? "" : result.toString();
+		} catch (NoSuchMethodException e) {
+			return null;
+		} catch (Exception e) {
+			throw new RuntimeException(e);
+		}
+	}
+
+	/*
+	 * Returns the text on a line of a terminal. Newer versions return a text
+	 * buffer rather than a string.
+	 */
+	public static String getLine(Terminal terminal, int line) {
+		String text = call(terminal, "getLine", line);
+		return text == null ? "" : text;
+	}
+
+	/*
+	 * Returns the colors on a line of a terminal, as the text color of each
+	 * cell followed by the background color of each cell. Newer versions keep
+	 * the text and background colors separately.
+	 */
+	public static String getColorLine(Terminal terminal, int line) {
+		String colors = call(terminal, "getColourLine", line);
+		if (colors != null) {
+			return colors;
+		}
+
+		String text = call(terminal, "getTextColourLine", line);
+		String background = call(terminal, "getBackgroundColourLine", line);
+		return (text == null ? "" : text) + (background == null ? "" : background);
+	}
+
+}
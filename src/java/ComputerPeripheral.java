@@ -0,0 +1,76 @@
+/*
+ * Computer Peripheral
+ *
+ * Another computer adjacent to a computer, wrapped as a `computer`
+ * peripheral.
+ */
+
+
+import dan200.computercraft.api.lua.ILuaContext;
+import dan200.computercraft.api.lua.LuaException;
+import dan200.computercraft.api.peripheral.IComputerAccess;
+import dan200.computercraft.api.peripheral.IPeripheral;
+
+
+public class ComputerPeripheral implements IPeripheral {
+
+	private Minion computer;
+
+	/*
+	 * Create a peripheral controlling the given computer.
+	 */
+	public ComputerPeripheral(Minion computer) {
+		this.computer = computer;
+	}
+
+	public Minion getComputer() {
+		return this.computer;
+	}
+
+	@Override
+	public String getType() {
+		return "computer";
+	}
+
+	@Override
+	public String[] getMethodNames() {
+		return new String[] {"turnOn", "shutdown", "reboot", "getID", "isOn"};
+	}
+
+	@Override
+	public Object[] callMethod(IComputerAccess access, ILuaContext context, int method,
+			Object[] arguments) throws LuaException, InterruptedException {
+		switch (method) {
+		case 0:
+			this.computer.turnOn();
+			return new Object[] {};
+		case 1:
+			this.computer.shutdown();
+			return new Object[] {};
+		case 2:
+			this.computer.reboot();
+			return new Object[] {};
+		case 3:
+			return new Object[] {new Integer(this.computer.id)};
+		case 4:
+			return new Object[] {new Boolean(this.computer.isOn())};
+		}
+
+		return new Object[] {};
+	}
+
+	@Override
+	public void attach(IComputerAccess access) {
+	}
+
+	@Override
+	public void detach(IComputerAccess access) {
+	}
+
+	@Override
+	public boolean equals(IPeripheral peripheral) {
+		return peripheral instanceof ComputerPeripheral &&
+			((ComputerPeripheral) peripheral).getComputer() == this.computer;
+	}
+
+}
@@ -0,0 +1,92 @@
+/*
+ * Faults
+ *
+ * Unreliable delivery applied to the messages on a network, for testing how
+ * programs cope with delayed, lost and reordered messages.
+ */
+
+
+import java.util.Map;
+import java.util.HashMap;
+import java.util.Random;
+
+
+public class Faults {
+
+	private int delay;
+	private double drop;
+	private double reorder;
+	private long seed;
+	private Map<String, Random> randoms;
+
+	/*
+	 * Create a set of faults. Every message is delayed by the given number of
+	 * ticks, lost with the probability `drop`, and held back for a few extra
+	 * ticks, letting later messages overtake it, with the probability
+	 * `reorder`. Each receiver makes its random choices from its own sequence,
+	 * seeded from the given seed and its name, so a run can be repeated no
+	 * matter what order computers' threads deliver messages in.
+	 */
+	public Faults(int delay, double drop, double reorder, long seed) {
+		this.delay = Math.max(0, delay);
+		this.drop = Math.max(0.0, Math.min(1.0, drop));
+		this.reorder = Math.max(0.0, Math.min(1.0, reorder));
+		this.seed = seed;
+		this.randoms = new HashMap<String, Random>();
+	}
+
+	/*
+	 * Returns true if no faults are applied at all.
+	 */
+	public boolean isNone() {
+		return this.delay == 0 && this.drop == 0.0 && this.reorder == 0.0;
+	}
+
+	/*
+	 * Returns the sequence of random choices for a receiver.
+	 */
+	private Random random(Receiver receiver) {
+		String name = receiver.getName();
+		Random random = this.randoms.get(name);
+		if (random == null) {
+			random = new Random(this.seed * 31 + name.hashCode());
+			this.randoms.put(name, random);
+		}
+
+		return random;
+	}
+
+	/*
+	 * Decides whether the next message to a receiver is lost.
+	 */
+	public synchronized boolean shouldDrop(Receiver receiver) {
+		return this.drop > 0.0 && this.random(receiver).nextDouble() < this.drop;
+	}
+
+	/*
+	 * Decides how many ticks to hold back the next message to a receiver for.
+	 */
+	public synchronized int nextDelay(Receiver receiver) {
+		Random random = this.random(receiver);
+		int ticks = this.delay;
+		if (this.reorder > 0.0 && random.nextDouble() < this.reorder) {
+			ticks += 1 + random.nextInt(this.delay + 2);
+		}
+
+		return ticks;
+	}
+
+	/*
+	 * Returns the seed used for random choices.
+	 */
+	public long getSeed() {
+		return this.seed;
+	}
+
+	@Override
+	public String toString() {
+		return "delay " + this.delay + " ticks, drop " + this.drop + ", reorder " +
+			this.reorder + ", seed " + this.seed;
+	}
+
+}
@@ -0,0 +1,358 @@
+/*
+ * Host
+ *
+ * Controls for the emulator that aren't tied to any single computer.
+ */
+
+
+import java.io.File;
+import java.io.IOException;
+import java.util.Map;
+import java.util.HashMap;
+import java.util.List;
+import java.util.ArrayList;
+import java.util.Queue;
+import java.util.Random;
+import java.util.concurrent.ConcurrentLinkedQueue;
+
+import dan200.computercraft.api.peripheral.IPeripheral;
+
+
+public class Host {
+
+	private String storageDirectory;
+	private boolean liveAudio;
+
+	private List<Speaker> speakers;
+
+	private Map<String, Subscriber> subscribers;
+	private Queue<String> received;
+
+	public Host(String storageDirectory, boolean liveAudio) {
+		this.storageDirectory = storageDirectory;
+		this.liveAudio = liveAudio;
+		this.speakers = new ArrayList<Speaker>();
+		this.subscribers = new HashMap<String, Subscriber>();
+		this.received = new ConcurrentLinkedQueue<String>();
+	}
+
+	/*
+	 * Returns the version of ComputerCraft in use, such as `1.65`.
+	 */
+	public String getComputerCraftVersion() {
+		return Compat.getVersion();
+	}
+
+	/*
+	 * Called once per frame, with the time in seconds since the last frame.
+	 */
+	public void advance(double delta) {
+		Network.advance(delta);
+
+		for (Speaker speaker : this.speakers) {
+			speaker.advance(delta);
+		}
+	}
+
+	/*
+	 * Called when Mimic exits, to finish writing any audio.
+	 */
+	public void shutdown() {
+		for (Speaker speaker : this.speakers) {
+			speaker.close();
+		}
+	}
+
+
+	/*
+	 * Sets the range of wireless modems at ground level and at the top of the
+	 * world.
+	 */
+	public void setModemRange(double min, double max) {
+		Network.setRange(min, max);
+	}
+
+	/*
+	 * Bridges two wireless networks together.
+	 */
+	public void bridge(String a, String b) {
+		Network.bridge(Network.get(a), Network.get(b));
+	}
+
+	/*
+	 * Removes the bridge between two wireless networks.
+	 */
+	public void unbridge(String a, String b) {
+		Network.unbridge(Network.get(a), Network.get(b));
+	}
+
+
+	/*
+	 * Sets the faults applied to messages on a wireless network. A negative
+	 * seed picks one at random. Returns a description of the faults now
+	 * applied.
+	 */
+	public String setFaults(String network, int delay, double drop, double reorder,
+			long seed) {
+		if (seed < 0) {
+			seed = new Random().nextInt(Integer.MAX_VALUE);
+		}
+
+		Network.get(network).setFaults(new Faults(delay, drop, reorder, seed));
+		return this.getFaults(network);
+	}
+
+	/*
+	 * Returns a description of the faults applied to messages on a wireless
+	 * network.
+	 */
+	public String getFaults(String network) {
+		Faults faults = Network.get(network).getFaults();
+		return faults == null ? "none" : faults.toString();
+	}
+
+
+	/*
+	 * Injecting Messages
+	 */
+
+	/*
+	 * Returns the subscriber used to send and receive messages on a network.
+	 */
+	private Subscriber getSubscriber(String network) {
+		Subscriber subscriber = this.subscribers.get(network);
+		if (subscriber == null) {
+			subscriber = new Subscriber(Network.get(network), this.received);
+			this.subscribers.put(network, subscriber);
+		}
+
+		return subscriber;
+	}
+
+	/*
+	 * Transmits a message written as a Lua value on a wireless network, as if
+	 * sent by an ender modem. Returns an error message if the message couldn't
+	 * be parsed, or an empty string.
+	 */
+	public String transmit(String network, int channel, int replyChannel, String text) {
+		Object message;
+		try {
+			message = Serializer.parse(text);
+		} catch (IllegalArgumentException e) {
+			return "Invalid message: " + e.getMessage();
+		}
+
+		Subscriber subscriber = this.getSubscriber(network);
+		subscriber.getNetwork().transmit(subscriber, message, channel, replyChannel);
+		return "";
+	}
+
+	/*
+	 * Starts recording messages sent on a channel of a wireless network.
+	 */
+	public void subscribe(String network, int channel) {
+		Subscriber subscriber = this.getSubscriber(network);
+		subscriber.getNetwork().listen(subscriber, channel);
+	}
+
+	/*
+	 * Stops recording messages sent on a channel of a wireless network.
+	 */
+	public void unsubscribe(String network, int channel) {
+		Subscriber subscriber = this.getSubscriber(network);
+		subscriber.getNetwork().unlisten(subscriber, channel);
+	}
+
+	/*
+	 * Returns every message received on a subscribed channel since the last
+	 * call, one per line, each as a tab separated network, channel, reply
+	 * channel, distance, and message.
+	 */
+	public String pollMessages() {
+		StringBuilder builder = new StringBuilder();
+		String line;
+		while ((line = this.received.poll()) != null) {
+			builder.append(line).append("\n");
+		}
+
+		return builder.toString();
+	}
+
+
+	/*
+	 * Starts recording modem traffic to the log file in the logs folder.
+	 */
+	public void openModemLog() {
+		Sniffer.open(this.storageDirectory + File.separator + "logs" + File.separator +
+			"modem.log");
+	}
+
+	/*
+	 * Sets whether modem traffic is printed to the console.
+	 */
+	public void setModemLogConsole(boolean enabled) {
+		Sniffer.setConsole(enabled);
+	}
+
+	/*
+	 * Returns whether modem traffic is printed to the console.
+	 */
+	public boolean isModemLogConsole() {
+		return Sniffer.isConsole();
+	}
+
+	/*
+	 * Archives
+	 */
+
+	/*
+	 * Returns a value from the metadata of a computer archive, or an empty
+	 * string if it's missing or the archive can't be read.
+	 */
+	public String getArchiveMetadata(String path, String key) {
+		try {
+			return Archive.readMetadata(new File(path)).getProperty(key, "");
+		} catch (IOException e) {
+			return "";
+		}
+	}
+
+	/*
+	 * Extracts the files in a computer archive into a directory, handling
+	 * existing files as described by the mode, one of `fail`, `replace` or
+	 * `merge`. Returns an error message, or an empty string.
+	 */
+	public String extractArchive(String path, String directory, String mode) {
+		try {
+			Archive.extract(new File(path), new File(directory), mode);
+		} catch (IOException e) {
+			return "Failed to import " + path + ": " + e.getMessage();
+		}
+
+		return "";
+	}
+
+
+	/*
+	 * Starts recording the commands issued by command computers to the log
+	 * file in the logs folder.
+	 */
+	public void openCommandLog() {
+		Commands.openLog(this.storageDirectory + File.separator + "logs" + File.separator +
+			"commands.log");
+	}
+
+	/*
+	 * Scripts the result of a command run by command computers. The output is
+	 * given as lines separated by newlines.
+	 */
+	public void setCommandResult(String command, boolean success, String output) {
+		CommandHandler handler = Commands.getHandler();
+		if (!(handler instanceof ScriptedCommandHandler)) {
+			return;
+		}
+
+		String[] lines = output.length() == 0 ? new String[0] : output.split("\n");
+		((ScriptedCommandHandler) handler).setResult(command, new CommandResult(success, lines));
+	}
+
+	/*
+	 * Listens for other Mimic processes linking to this one on the given port.
+	 */
+	public void listenLink(int port) {
+		Link.listen(port);
+	}
+
+	/*
+	 * Links to another Mimic process at an address of the form `host:port`,
+	 * returning false if the address is invalid.
+	 */
+	public boolean connectLink(String address) {
+		int separator = address.lastIndexOf(':');
+		if (separator < 0) {
+			return false;
+		}
+
+		try {
+			int port = Integer.parseInt(address.substring(separator + 1));
+			Link.connect(address.substring(0, separator), port);
+			return true;
+		} catch (NumberFormatException e) {
+			return false;
+		}
+	}
+
+
+	/*
+	 * World
+	 */
+
+	/*
+	 * Loads the blocks in the turtles' world from a file, returning an error
+	 * message, or an empty string.
+	 */
+	public String loadWorld(String path) {
+		try {
+			World.get().load(path);
+			return "";
+		} catch (IOException e) {
+			return "Failed to load world " + path + ": " + e.getMessage();
+		}
+	}
+
+
+	/*
+	 * Cables
+	 */
+
+	/*
+	 * Places a new peripheral of the given type on a cable network, returning
+	 * its network name, or an empty string if the type isn't supported.
+	 */
+	public String attachCablePeripheral(String cableName, String type) {
+		if (!type.equals("modem") && !type.equals("speaker")) {
+			return "";
+		}
+
+		Cable cable = Cable.get(cableName);
+		String name = cable.nextName(type);
+
+		IPeripheral peripheral;
+		if (type.equals("speaker")) {
+			String audioDirectory = this.storageDirectory + File.separator + "audio";
+			Speaker speaker = new Speaker(audioDirectory, cableName + "_" + name,
+				this.liveAudio);
+			this.speakers.add(speaker);
+			peripheral = speaker;
+		} else {
+			peripheral = new Modem();
+		}
+
+		cable.attachPeripheral(name, peripheral);
+		return name;
+	}
+
+	/*
+	 * Removes a peripheral from a cable network.
+	 */
+	public void detachCablePeripheral(String cableName, String name) {
+		IPeripheral peripheral = Cable.get(cableName).detachPeripheral(name);
+		if (peripheral instanceof Speaker) {
+			((Speaker) peripheral).close();
+			this.speakers.remove(peripheral);
+		}
+	}
+
+	/*
+	 * Returns the names of every peripheral on a cable network, one per line.
+	 */
+	public String getCablePeripherals(String cableName) {
+		StringBuilder builder = new StringBuilder();
+		for (String name : Cable.get(cableName).getPeripheralNames()) {
+			builder.append(name).append("\n");
+		}
+
+		return builder.toString();
+	}
+
+}
@@ -0,0 +1,280 @@
+/*
+ * Link
+ *
+ * Forwards wireless modem traffic between Mimic processes over TCP.
+ *
+ * Each message is sent as a single line of UTF-8 text, made up of tab
+ * separated fields:
+ *
+ *   network  channel  replyChannel  x  y  z  range  message
+ *
+ * The position and range are those of the sending modem, with a negative
+ * range for ender modems. The message is written using `Serializer`.
+ */
+
+
+import java.io.BufferedReader;
+import java.io.IOException;
+import java.io.InputStreamReader;
+import java.io.OutputStreamWriter;
+import java.io.Writer;
+import java.net.ServerSocket;
+import java.net.Socket;
+import java.util.List;
+import java.util.ArrayList;
+import java.util.concurrent.BlockingQueue;
+import java.util.concurrent.LinkedBlockingQueue;
+
+
+public class Link {
+
+	/*
+	 * How long to wait before reconnecting to a peer, in milliseconds.
+	 */
+	private static final long RECONNECT_DELAY = 5000;
+
+	/*
+	 * How many messages can be waiting to be sent to a peer before new ones
+	 * are dropped, so a peer that stops reading can't use up all our memory.
+	 */
+	private static final int QUEUE_LIMIT = 4096;
+
+	private static List<Connection> connections = new ArrayList<Connection>();
+
+	/*
+	 * A message received from another Mimic process. Acts as the sender when
+	 * the message is transmitted on the local network.
+	 */
+	public static class Packet implements Receiver {
+
+		private double[] position;
+		private double range;
+
+		public Packet(double[] position, double range) {
+			this.position = position;
+			this.range = range;
+		}
+
+		public double[] getPosition() {
+			return this.position;
+		}
+
+		public double getRange() {
+			return this.range;
+		}
+
+		public String getName() {
+			return "link";
+		}
+
+		public void receive(Object message, int channel, int replyChannel, double distance) {}
+
+	}
+
+	/*
+	 * Listens for connections from other Mimic processes on the given port.
+	 */
+	public static void listen(final int port) {
+		Thread thread = new Thread(new Runnable() {
+			public void run() {
+				try {
+					ServerSocket server = new ServerSocket(port);
+					while (true) {
+						Link.handle(server.accept());
+					}
+				} catch (IOException e) {
+					System.out.println("Link: failed to listen on port " + port + ": " +
+						e.getMessage());
+				}
+			}
+		});
+
+		thread.setDaemon(true);
+		thread.start();
+	}
+
+	/*
+	 * Connects to another Mimic process, reconnecting whenever the connection
+	 * is lost.
+	 */
+	public static void connect(final String host, final int port) {
+		Thread thread = new Thread(new Runnable() {
+			public void run() {
+				while (true) {
+					try {
+						Link.read(new Socket(host, port));
+					} catch (IOException e) {
+						// Not running yet, try again shortly
+					}
+
+					try {
+						Thread.sleep(RECONNECT_DELAY);
+					} catch (InterruptedException e) {
+						return;
+					}
+				}
+			}
+		});
+
+		thread.setDaemon(true);
+		thread.start();
+	}
+
+	/*
+	 * Reads from an accepted connection on a new thread.
+	 */
+	private static void handle(final Socket socket) {
+		Thread thread = new Thread(new Runnable() {
+			public void run() {
+				try {
+					Link.read(socket);
+				} catch (IOException e) {
+					// Connection closed
+				}
+			}
+		});
+
+		thread.setDaemon(true);
+		thread.start();
+	}
+
+	/*
+	 * Registers a connection for sending, then injects each message received
+	 * on it into the local network until it closes.
+	 */
+	private static void read(Socket socket) throws IOException {
+		Connection connection = new Connection(socket);
+		synchronized (connections) {
+			connections.add(connection);
+		}
+
+		try {
+			BufferedReader reader = new BufferedReader(
+				new InputStreamReader(socket.getInputStream(), "UTF-8"));
+
+			String line;
+			while ((line = reader.readLine()) != null) {
+				try {
+					Link.inject(line);
+				} catch (IllegalArgumentException e) {
+					System.out.println("Link: ignoring invalid message: " + e.getMessage());
+				}
+			}
+		} finally {
+			synchronized (connections) {
+				connections.remove(connection);
+			}
+			connection.close();
+		}
+	}
+
+	/*
+	 * Transmits a received line on the local network.
+	 */
+	private static void inject(String line) throws IllegalArgumentException {
+		String[] fields = line.split("\t", 8);
+		if (fields.length != 8) {
+			throw new IllegalArgumentException("Expected 8 fields");
+		}
+
+		try {
+			Network network = Network.get(fields[0]);
+			int channel = Integer.parseInt(fields[1]);
+			int replyChannel = Integer.parseInt(fields[2]);
+			double[] position = new double[] {
+				Double.parseDouble(fields[3]),
+				Double.parseDouble(fields[4]),
+				Double.parseDouble(fields[5]),
+			};
+			double range = Double.parseDouble(fields[6]);
+			Object message = Serializer.parse(fields[7]);
+
+			network.transmit(new Packet(position, range), message, channel, replyChannel);
+		} catch (NumberFormatException e) {
+			throw new IllegalArgumentException("Invalid number");
+		}
+	}
+
+	/*
+	 * Queues a message transmitted on the local network to be sent to every
+	 * connected Mimic process, without waiting for it to be sent. Messages
+	 * that arrived over the link aren't sent back.
+	 */
+	public static void forward(Network network, Receiver sender, Object message,
+			int channel, int replyChannel) {
+		if (sender instanceof Packet) {
+			return;
+		}
+
+		double[] position = sender.getPosition();
+		String line = network.getName() + "\t" + channel + "\t" + replyChannel + "\t" +
+			position[0] + "\t" + position[1] + "\t" + position[2] + "\t" +
+			sender.getRange() + "\t" + Serializer.serialize(message) + "\n";
+
+		synchronized (connections) {
+			for (Connection connection : connections) {
+				connection.send(line);
+			}
+		}
+	}
+
+
+	/*
+	 * A connection to another Mimic process, with its own thread writing
+	 * queued messages to it so a slow peer doesn't hold up the computers
+	 * sending them.
+	 */
+	private static class Connection implements Runnable {
+
+		private Socket socket;
+		private Writer writer;
+		private BlockingQueue<String> queue;
+		private Thread thread;
+
+		public Connection(Socket socket) throws IOException {
+			this.socket = socket;
+			this.writer = new OutputStreamWriter(socket.getOutputStream(), "UTF-8");
+			this.queue = new LinkedBlockingQueue<String>(QUEUE_LIMIT);
+			this.thread = new Thread(this);
+			this.thread.setDaemon(true);
+			this.thread.start();
+		}
+
+		/*
+		 * Queues a line to be sent, dropping it if the peer has fallen too
+		 * far behind.
+		 */
+		public void send(String line) {
+			this.queue.offer(line);
+		}
+
+		/*
+		 * Stops sending and closes the socket.
+		 */
+		public void close() {
+			this.thread.interrupt();
+
+			try {
+				this.socket.close();
+			} catch (IOException e) {
+				// Already closed
+			}
+		}
+
+		public void run() {
+			try {
+				while (true) {
+					this.writer.write(this.queue.take());
+					this.writer.flush();
+				}
+			} catch (InterruptedException e) {
+				// Closed
+			} catch (IOException e) {
+				// Closing the socket also ends the reading thread
+				this.close();
+			}
+		}
+
+	}
+
+}
@@ -9,10 +9,15 @@
 
 import java.io.File;
 import java.io.IOException;
+import java.util.Map;
+import java.util.HashMap;
+import java.util.List;
 import java.util.ArrayList;
+import java.util.Properties;
 
 import dan200.computercraft.api.filesystem.IMount;
 import dan200.computercraft.api.filesystem.IWritableMount;
+import dan200.computercraft.api.peripheral.IPeripheral;
 import dan200.computercraft.core.filesystem.ComboMount;
 import dan200.computercraft.core.filesystem.JarMount;
 import dan200.computercraft.core.filesystem.FileMount;
@@ -23,45 +28,130 @@ import dan200.computercraft.core.terminal.Terminal;
 
 public class Minion implements IComputerEnvironment {
 
+	/*
+	 * The names of each side of a computer, in ComputerCraft's order.
+	 */
+	public static final String[] SIDES = new String[] {
+		"bottom", "top", "back", "front", "right", "left",
+	};
+
+	/*
+	 * Every computer created, keyed by ID, so computers can find each other.
+	 */
+	private static Map<Integer, Minion> minions = new HashMap<Integer, Minion>();
+
 	public int id;
 	public boolean advanced;
 
 	private double ticks;
+	private double[] position;
 
 	private Computer computer;
 	private Terminal terminal;
+	private boolean bootPending;
 
 	private String storageDirectory;
 	private String romDirectory;
+	private boolean globalRom;
+	private List<String> romLayers;
 
 	private long spaceLimit;
+	private boolean liveAudio;
+
+	private IPeripheral[] peripherals;
+	private int[] redstoneInputs;
+	private int[] bundledInputs;
+	private int[] reportedOutputs;
+	private int[] reportedBundledOutputs;
+	private Turtle turtle;
+	private Pocket pocket;
+	private String saveDirectory;
+	private String startup;
+	private List<String[]> mounts;
+	private String network;
 
 	public Minion(int id, boolean advanced, int width, int height,
-			String storageDirectory, String romDirectory, long spaceLimit) {
+			String storageDirectory, String romDirectory, long spaceLimit,
+			boolean liveAudio) {
 		this.id = id;
 		this.advanced = advanced;
 		this.storageDirectory = storageDirectory;
 		this.romDirectory = romDirectory;
+		this.globalRom = true;
+		this.romLayers = new ArrayList<String>();
 		this.spaceLimit = spaceLimit;
+		this.liveAudio = liveAudio;
+		this.position = new double[] {0.0, 0.0, 0.0};
+		this.peripherals = new IPeripheral[SIDES.length];
+		this.redstoneInputs = new int[SIDES.length];
+		this.bundledInputs = new int[SIDES.length];
+		this.reportedOutputs = new int[SIDES.length];
+		this.reportedBundledOutputs = new int[SIDES.length];
+		this.turtle = null;
+		this.saveDirectory = null;
+		this.startup = null;
+		this.mounts = new ArrayList<String[]>();
+		this.network = Network.DEFAULT;
 		this.terminal = new Terminal(width, height);
 		this.computer = new Computer(this, this.terminal, id);
-		this.computer.turnOn();
+
+		synchronized (minions) {
+			minions.put(new Integer(id), this);
+		}
+	}
+
+	/*
+	 * Returns the computer with the given ID, or null.
+	 */
+	public static Minion get(int id) {
+		synchronized (minions) {
+			return minions.get(new Integer(id));
+		}
 	}
 
 	public String getLine(int line) {
-		return this.terminal.getLine(line);
+		return Compat.getLine(this.terminal, line);
 	}
 
 	public String getColorLine(int line) {
-		return this.terminal.getColourLine(line);
+		return Compat.getColorLine(this.terminal, line);
 	}
 
 	public void advance(double delta) {
 		this.ticks += delta * 20;
 		this.computer.advance(delta);
+
+		// Boot again once a shutdown to replace the computer's files finishes
+		if (this.bootPending) {
+			if (this.computer.isOn()) {
+				this.bootPending = false;
+			} else {
+				this.computer.turnOn();
+			}
+		}
+
+		for (IPeripheral peripheral : this.peripherals) {
+			if (peripheral instanceof Speaker) {
+				((Speaker) peripheral).advance(delta);
+			}
+		}
 	}
 
 	public void destroy() {
+		for (IPeripheral peripheral : this.peripherals) {
+			if (peripheral instanceof Speaker) {
+				((Speaker) peripheral).close();
+			}
+		}
+
+		if (this.turtle != null) {
+			World.get().removeTurtle(this.turtle);
+		}
+
+		synchronized (minions) {
+			minions.remove(new Integer(this.id));
+		}
+
 		this.computer.shutdown();
 		this.computer.unload();
 	}
@@ -87,18 +177,662 @@ public class Minion implements IComputerEnvironment {
 		return this.terminal.getCursorBlink();
 	}
 
+	public double[] getPosition() {
+		return this.position;
+	}
+
+	public void setPosition(double x, double y, double z) {
+		this.position = new double[] {x, y, z};
+	}
 
 	/*
-	 * Events
+	 * Sets the directory holding the computer's files, instead of its folder
+	 * under `computers` in the storage directory. Takes effect the next time
+	 * the computer boots.
+	 */
+	public void setSaveDirectory(String directory) {
+		this.saveDirectory = directory;
+	}
+
+	/*
+	 * Overlays a read only startup program on the computer's files, without
+	 * changing anything in its save directory. Takes effect the next time the
+	 * computer boots.
+	 */
+	public void setStartup(String program) {
+		this.startup = program;
+	}
+
+	/*
+	 * Returns the directory holding the computer's files.
+	 */
+	public File getSaveDirectory() {
+		if (this.saveDirectory != null) {
+			return new File(this.saveDirectory);
+		}
+
+		return new File(this.storageDirectory + File.separator + "computers",
+			Integer.toString(this.id));
+	}
+
+	public String getLabel() {
+		String label = this.computer.getLabel();
+		return label == null ? "" : label;
+	}
+
+	/*
+	 * Sets the computer's label, or removes it if the label is empty.
+	 */
+	public void setLabel(String label) {
+		this.computer.setLabel(label.length() == 0 ? null : label);
+	}
+
+	/*
+	 * Writes the computer's files to a zip archive, along with its ID, label,
+	 * type, and whether it's advanced. Returns an error message, or an empty
+	 * string.
+	 */
+	public String exportArchive(String path, String kind) {
+		Properties metadata = new Properties();
+		metadata.setProperty("id", Integer.toString(this.id));
+		metadata.setProperty("label", this.getLabel());
+		metadata.setProperty("kind", kind);
+		metadata.setProperty("advanced", Boolean.toString(this.advanced));
+		metadata.setProperty("computercraft", Compat.getVersion());
+
+		try {
+			Archive.export(this.getSaveDirectory(), new File(path), metadata);
+		} catch (IOException e) {
+			return "Failed to export computer " + this.id + ": " + e.getMessage();
+		}
+
+		return "";
+	}
+
+	/*
+	 * Replaces the computer's files and label with those in a zip archive,
+	 * handling existing files as described by the mode, one of `fail`,
+	 * `replace` or `merge`. A computer that's on is shut down while its files
+	 * are replaced, and boots again afterwards. Returns an error message, or
+	 * an empty string.
+	 */
+	public String importArchive(String path, String mode) {
+		boolean on = this.stopForFiles();
+		try {
+			Properties metadata = Archive.readMetadata(new File(path));
+			Archive.extract(new File(path), this.getSaveDirectory(), mode);
+			this.setLabel(metadata.getProperty("label", ""));
+		} catch (IOException e) {
+			return "Failed to import " + path + ": " + e.getMessage();
+		} finally {
+			if (on) {
+				this.bootPending = true;
+			}
+		}
+
+		return "";
+	}
+
+	/*
+	 * Shuts the computer down if it's on, so it doesn't see its files half
+	 * replaced. Returns true if it was on.
+	 */
+	private boolean stopForFiles() {
+		boolean on = this.computer.isOn();
+		if (on) {
+			this.computer.shutdown();
+		}
+
+		return on;
+	}
+
+	/*
+	 * Returns the folder holding the computer's snapshots.
+	 */
+	private File getSnapshotDirectory() {
+		return new File(this.storageDirectory + File.separator + "snapshots",
+			Integer.toString(this.id));
+	}
+
+	/*
+	 * Returns the snapshot with the given name, or null if the name isn't a
+	 * snapshot number.
+	 */
+	private File getSnapshot(String name) {
+		try {
+			Integer.parseInt(name);
+		} catch (NumberFormatException e) {
+			return null;
+		}
+
+		return new File(this.getSnapshotDirectory(), name);
+	}
+
+	/*
+	 * Copies the computer's files into a new snapshot, which becomes its
+	 * latest. Returns an error message, or an empty string.
+	 */
+	public String takeSnapshot() {
+		try {
+			Snapshots.take(this.getSaveDirectory(), this.getSnapshotDirectory());
+		} catch (IOException e) {
+			return "Failed to snapshot computer " + this.id + ": " + e.getMessage();
+		}
+
+		return "";
+	}
+
+	/*
+	 * Returns one line for each of the computer's snapshots, oldest first, with
+	 * tab separated name, time taken and size in bytes.
+	 */
+	public String getSnapshots() {
+		return Snapshots.list(this.getSnapshotDirectory());
+	}
+
+	/*
+	 * Returns the name of the computer's most recent snapshot, or an empty
+	 * string if it has none.
+	 */
+	public String getLatestSnapshot() {
+		int latest = Snapshots.latest(this.getSnapshotDirectory());
+		return latest == 0 ? "" : Integer.toString(latest);
+	}
+
+	/*
+	 * Replaces the computer's files with those in a snapshot. A computer
+	 * that's on is shut down while its files are replaced, and boots again
+	 * afterwards. Returns an error message, or an empty string.
+	 */
+	public String rollback(String name) {
+		File snapshot = this.getSnapshot(name);
+		if (snapshot == null || !snapshot.isDirectory()) {
+			return "Computer " + this.id + " has no snapshot named `" + name + "`";
+		}
+
+		boolean on = this.stopForFiles();
+		try {
+			Snapshots.restore(snapshot, this.getSaveDirectory());
+		} catch (IOException e) {
+			return "Failed to roll back computer " + this.id + ": " + e.getMessage();
+		} finally {
+			if (on) {
+				this.bootPending = true;
+			}
+		}
+
+		return "";
+	}
+
+	/*
+	 * Deletes one of the computer's snapshots. Returns an error message, or an
+	 * empty string.
+	 */
+	public String deleteSnapshot(String name) {
+		File snapshot = this.getSnapshot(name);
+		if (snapshot == null || !snapshot.isDirectory()) {
+			return "Computer " + this.id + " has no snapshot named `" + name + "`";
+		}
+
+		try {
+			Snapshots.delete(snapshot);
+		} catch (IOException e) {
+			return "Failed to delete snapshot " + name + ": " + e.getMessage();
+		}
+
+		return "";
+	}
+
+	/*
+	 * Sets whether the `rom` folder in the storage directory is layered over
+	 * the computer's ROM. Takes effect the next time the computer boots.
+	 */
+	public void setGlobalRom(boolean enabled) {
+		this.globalRom = enabled;
+	}
+
+	/*
+	 * Layers a directory over the computer's ROM, on top of any layers added
+	 * before it. Takes effect the next time the computer boots.
+	 */
+	public void addRomLayer(String directory) {
+		this.romLayers.add(directory);
+	}
+
+	/*
+	 * Overlays a directory on the host at a path in the computer's files,
+	 * such as `/lib`, replacing any mount already at that path. Takes effect
+	 * the next time the computer boots. Returns an error message, or an empty
+	 * string.
 	 */
+	public String addMount(String path, String directory, boolean readOnly) {
+		String location = path.replaceAll("^/+|/+$", "").replaceAll("/+", "/");
+		if (location.length() == 0 || location.equals("rom") || location.startsWith("rom/")) {
+			return "Can't mount over `/" + location + "`";
+		}
+
+		File file = new File(directory);
+		if (readOnly && !file.isDirectory()) {
+			return "No such directory `" + directory + "`";
+		} else if (file.exists() && !file.isDirectory()) {
+			return "`" + directory + "` is not a directory";
+		}
+
+		this.removeMount(location);
+		this.mounts.add(new String[] {location, directory, readOnly ? "ro" : "rw"});
+		return "";
+	}
+
+	/*
+	 * Removes the directory mounted at a path, returning false if there's
+	 * nothing mounted there. Takes effect the next time the computer boots.
+	 */
+	public boolean removeMount(String path) {
+		String location = path.replaceAll("^/+|/+$", "").replaceAll("/+", "/");
+		for (int i = 0; i < this.mounts.size(); i++) {
+			if (this.mounts.get(i)[0].equals(location)) {
+				this.mounts.remove(i);
+				return true;
+			}
+		}
+
+		return false;
+	}
+
+	/*
+	 * Returns the directories mounted in the computer, one per line, each as
+	 * a tab separated path, directory, and `ro` or `rw`.
+	 */
+	public String getMounts() {
+		StringBuilder builder = new StringBuilder();
+		for (String[] mount : this.mounts) {
+			builder.append("/").append(mount[0]).append("\t").append(mount[1])
+				.append("\t").append(mount[2]).append("\n");
+		}
+
+		return builder.toString();
+	}
+
+
+	/*
+	 * Peripherals
+	 */
+
+	/*
+	 * Returns the index of the side with the given name, or -1.
+	 */
+	public static int sideIndex(String side) {
+		for (int i = 0; i < SIDES.length; i++) {
+			if (SIDES[i].equals(side)) {
+				return i;
+			}
+		}
+
+		return -1;
+	}
+
+	/*
+	 * Replaces the peripheral on a side, closing a speaker that's removed so
+	 * the audio it recorded is saved.
+	 */
+	private void setPeripheral(int side, IPeripheral peripheral) {
+		IPeripheral previous = this.peripherals[side];
+		if (previous != null && previous != peripheral && previous instanceof Speaker) {
+			((Speaker) previous).close();
+		}
+
+		this.peripherals[side] = peripheral;
+		this.computer.setPeripheral(side, peripheral);
+	}
+
+	public boolean hasPeripheral(String side) {
+		return this.peripherals[sideIndex(side)] != null;
+	}
+
+	/*
+	 * Returns the peripheral on a side, or null.
+	 */
+	public IPeripheral getPeripheral(String side) {
+		return this.peripherals[sideIndex(side)];
+	}
+
+	public void detachPeripheral(String side) {
+		this.setPeripheral(sideIndex(side), null);
+	}
 
 	public void attachModem() {
-		Modem peripheral = new Modem();
-		this.computer.setPeripheral(1, peripheral);
+		this.setPeripheral(1, new Modem(this, Network.get(this.network), null, false));
+	}
+
+	public void attachEnderModem() {
+		this.setPeripheral(1, new Modem(this, Network.get(this.network), null, true));
 	}
 
 	public void detachModem() {
-		this.computer.setPeripheral(2, null);
+		this.setPeripheral(1, null);
+	}
+
+	public void attachWiredModem(String side, String cable) {
+		this.setPeripheral(sideIndex(side), new Modem(this, null, Cable.get(cable), false));
+	}
+
+	/*
+	 * Places the computer with the given ID on a side, as a `computer`
+	 * peripheral. Returns false if there's no computer with that ID.
+	 */
+	public boolean attachComputer(String side, int id) {
+		Minion other = Minion.get(id);
+		if (other == null) {
+			return false;
+		}
+
+		this.setPeripheral(sideIndex(side), new ComputerPeripheral(other));
+		return true;
+	}
+
+	/*
+	 * Returns true if there's a speaker on a side.
+	 */
+	public boolean hasSpeaker(String side) {
+		return this.peripherals[sideIndex(side)] instanceof Speaker;
+	}
+
+	/*
+	 * Places a speaker on a side. On the back of a pocket computer it's
+	 * equipped as the pocket's upgrade instead, so the `pocket` API sees it.
+	 */
+	public void attachSpeaker(String side) {
+		int index = sideIndex(side);
+		if (this.pocket != null && index == 2) {
+			this.pocket.swap("speaker");
+		} else {
+			this.attachSpeaker(index);
+		}
+	}
+
+	/*
+	 * Places a speaker on a side, recording to a file named after the
+	 * computer and the side so several speakers don't overwrite each other.
+	 */
+	private void attachSpeaker(int side) {
+		String audioDirectory = this.storageDirectory + File.separator + "audio";
+		String name = "computer_" + this.id + "_" + SIDES[side];
+		this.setPeripheral(side, new Speaker(audioDirectory, name, this.liveAudio));
+	}
+
+	/*
+	 * Removes the speaker on a side, if there is one, saving its audio. A
+	 * pocket computer's speaker upgrade is put back with its spares.
+	 */
+	public void detachSpeaker(String side) {
+		int index = sideIndex(side);
+		if (!(this.peripherals[index] instanceof Speaker)) {
+			return;
+		}
+
+		if (this.pocket != null && index == 2) {
+			this.pocket.swap(null);
+		} else {
+			this.setPeripheral(index, null);
+		}
+	}
+
+	/*
+	 * Sets the network used by wireless modems attached to the computer from
+	 * now on, and moves any already attached onto it.
+	 */
+	public void setNetwork(String name) {
+		this.network = name;
+		for (IPeripheral peripheral : this.peripherals) {
+			if (peripheral instanceof Modem && ((Modem) peripheral).isWireless()) {
+				((Modem) peripheral).setNetwork(Network.get(name));
+			}
+		}
+	}
+
+	/*
+	 * Moves the wireless modem on the given side onto a network, returning
+	 * false if there's no wireless modem on that side.
+	 */
+	public boolean setModemNetwork(String side, String name) {
+		IPeripheral peripheral = this.peripherals[sideIndex(side)];
+		if (!(peripheral instanceof Modem) || !((Modem) peripheral).isWireless()) {
+			return false;
+		}
+
+		((Modem) peripheral).setNetwork(Network.get(name));
+		return true;
+	}
+
+
+	/*
+	 * Pocket Computer
+	 */
+
+	/*
+	 * Makes the computer a pocket computer with the `pocket` API, equipping
+	 * an upgrade on its back, or none if the upgrade is empty. Spare upgrades
+	 * for `pocket.equipBack` are separated by newlines. Returns an error
+	 * message if an upgrade is unknown, or an empty string.
+	 */
+	public String makePocket(String upgrade, String spareUpgrades) {
+		List<String> spares = new ArrayList<String>();
+		for (String spare : spareUpgrades.split("\n")) {
+			if (spare.length() == 0) {
+				continue;
+			} else if (!Pocket.isUpgrade(spare)) {
+				return "Unknown pocket upgrade `" + spare + "`";
+			}
+
+			spares.add(spare);
+		}
+
+		if (upgrade.length() > 0 && !Pocket.isUpgrade(upgrade)) {
+			return "Unknown pocket upgrade `" + upgrade + "`";
+		}
+
+		this.pocket = new Pocket(this, spares);
+		this.computer.addAPI(this.pocket);
+		this.pocket.setEquipped(upgrade.length() > 0 ? upgrade : null);
+		return "";
+	}
+
+	/*
+	 * Places the peripheral for an upgrade on the back of the computer, or
+	 * removes it if the upgrade is null.
+	 */
+	public void setBackUpgrade(String upgrade) {
+		if (upgrade == null) {
+			this.setPeripheral(2, null);
+		} else if (upgrade.equals("modem")) {
+			this.setPeripheral(2, new Modem(this, Network.get(this.network), null, false));
+		} else if (upgrade.equals("ender modem")) {
+			this.setPeripheral(2, new Modem(this, Network.get(this.network), null, true));
+		} else if (upgrade.equals("speaker")) {
+			this.attachSpeaker(2);
+		}
+	}
+
+
+	/*
+	 * Turtle
+	 */
+
+	/*
+	 * Makes the computer a turtle in the shared world, facing north at the
+	 * computer's position. Must be called before the computer is turned on.
+	 */
+	public void makeTurtle(boolean needsFuel) {
+		this.turtle = new Turtle(this, World.get(), needsFuel);
+		this.computer.addAPI(this.turtle);
+		World.get().addTurtle(this.turtle);
+	}
+
+	/*
+	 * Makes the computer a command computer, with the `commands` API. Must be
+	 * called before the computer is turned on.
+	 */
+	public void makeCommandComputer() {
+		this.computer.addAPI(new Commands(this));
+	}
+
+	public Turtle getTurtle() {
+		return this.turtle;
+	}
+
+	/*
+	 * Returns a turtle's block coordinates, separated by spaces.
+	 */
+	public String getTurtlePosition() {
+		if (this.turtle == null) {
+			return "";
+		}
+
+		int[] position = this.turtle.getBlockPosition();
+		return position[0] + " " + position[1] + " " + position[2];
+	}
+
+	public String getTurtleFuel() {
+		return this.turtle == null ? "" : this.turtle.describeFuel();
+	}
+
+	public String getTurtleDirection() {
+		return this.turtle == null ? "" : this.turtle.getDirection();
+	}
+
+	/*
+	 * Returns the selected inventory slot, numbered from 1 to 16.
+	 */
+	public int getTurtleSelectedSlot() {
+		return this.turtle == null ? 0 : this.turtle.getSelectedSlot() + 1;
+	}
+
+	/*
+	 * Returns the name of the item in a slot, numbered from 1 to 16, or an
+	 * empty string if it's empty.
+	 */
+	public String getTurtleItem(int slot) {
+		if (this.turtle == null || this.turtle.getItem(slot - 1) == null) {
+			return "";
+		}
+
+		return this.turtle.getItem(slot - 1);
+	}
+
+	public int getTurtleItemCount(int slot) {
+		return this.turtle == null ? 0 : this.turtle.getItemCount(slot - 1);
+	}
+
+	/*
+	 * Returns how an item is drawn in the world view, as a character followed
+	 * by its text and background colors.
+	 */
+	public String getItemSymbol(String item) {
+		return World.symbol(item);
+	}
+
+	/*
+	 * Draws the world around a turtle, as described in Turtle.renderSlice.
+	 */
+	public String renderWorld(int width, int height) {
+		return this.turtle == null ? "" : this.turtle.renderSlice(width, height);
+	}
+
+	public void setTurtleFuel(int fuel) {
+		if (this.turtle != null) {
+			this.turtle.setFuelLevel(fuel);
+		}
+	}
+
+	/*
+	 * Puts items in a turtle's inventory slot, numbered from 1 to 16.
+	 */
+	public void setTurtleItem(int slot, String name, int count) {
+		if (this.turtle != null && slot >= 1 && slot <= Turtle.SLOTS) {
+			this.turtle.setItem(slot - 1, name, count);
+		}
+	}
+
+
+	/*
+	 * Redstone
+	 */
+
+	/*
+	 * Sets the redstone signal strength, from 0 to 15, entering a side of the
+	 * computer. The computer queues a `redstone` event when an input changes.
+	 */
+	public void setRedstoneInput(String side, int level) {
+		int index = sideIndex(side);
+		this.redstoneInputs[index] = Math.max(0, Math.min(15, level));
+		this.computer.setRedstoneInput(index, this.redstoneInputs[index]);
+	}
+
+	public int getRedstoneInput(String side) {
+		return this.redstoneInputs[sideIndex(side)];
+	}
+
+	/*
+	 * Sets the colors entering a side of the computer through bundled cable,
+	 * as a 16 bit combination like the `colors` API.
+	 */
+	public void setBundledInput(String side, int colors) {
+		int index = sideIndex(side);
+		this.bundledInputs[index] = colors & 0xFFFF;
+		this.computer.setBundledRedstoneInput(index, this.bundledInputs[index]);
+	}
+
+	public int getBundledInput(String side) {
+		return this.bundledInputs[sideIndex(side)];
+	}
+
+	/*
+	 * Returns the redstone signal strength the computer is emitting from a
+	 * side.
+	 */
+	public int getRedstoneOutput(String side) {
+		return this.computer.getRedstoneOutput(sideIndex(side));
+	}
+
+	/*
+	 * Returns the colors the computer is emitting from a side through bundled
+	 * cable.
+	 */
+	public int getBundledOutput(String side) {
+		return this.computer.getBundledRedstoneOutput(sideIndex(side));
+	}
+
+	/*
+	 * Returns a line of `side\tlevel\tcolors` for each side whose outputs
+	 * changed since the last call, so the emulator only has to ask once per
+	 * frame.
+	 */
+	public String getChangedOutputs() {
+		StringBuilder changes = new StringBuilder();
+		for (int i = 0; i < SIDES.length; i++) {
+			int level = this.computer.getRedstoneOutput(i);
+			int colors = this.computer.getBundledRedstoneOutput(i);
+			if (level == this.reportedOutputs[i] &&
+					colors == this.reportedBundledOutputs[i]) {
+				continue;
+			}
+
+			this.reportedOutputs[i] = level;
+			this.reportedBundledOutputs[i] = colors;
+			changes.append(SIDES[i]).append("\t").append(level).append("\t")
+				.append(colors).append("\n");
+		}
+
+		return changes.toString();
+	}
+
+
+	/*
+	 * Events
+	 */
+
+	public void queueEvent(String event, Object[] arguments) {
+		this.computer.queueEvent(event, arguments);
 	}
 
 	public void paste(String contents) {
@@ -145,10 +879,18 @@ public class Minion implements IComputerEnvironment {
 		this.computer.queueEvent("terminate", new Object[] {});
 	}
 
+	public void turnOn() {
+		this.computer.turnOn();
+	}
+
 	public void shutdown() {
 		this.computer.shutdown();
 	}
 
+	public boolean isOn() {
+		return this.computer.isOn();
+	}
+
 	public void reboot() {
 		if (this.computer.isOn()) {
 			this.computer.reboot();
@@ -187,6 +929,13 @@ public class Minion implements IComputerEnvironment {
 		return this.id;
 	}
 
+	/*
+	 * Returned by `_HOST` in newer versions of ComputerCraft.
+	 */
+	public String getHostString() {
+		return "ComputerCraft " + Compat.getVersion() + " (Mimic)";
+	}
+
 	@Override
 	public IWritableMount createSaveDirMount(String path, long size) {
 		// To create the save folder for a computer
@@ -194,10 +943,24 @@ public class Minion implements IComputerEnvironment {
 		//  path: computer/0
 		//  capacity: 2097152
 
-		String[] components = path.split(File.separator);
-		String computerID = components[components.length - 1];
-		File file = new File(this.storageDirectory + File.separator + "computers", computerID);
-		return new FileMount(file, size);
+		File file = this.getSaveDirectory();
+
+		FileMount mount = new FileMount(file, size);
+		if (this.startup == null && this.mounts.size() == 0) {
+			return mount;
+		}
+
+		SaveMount save = new SaveMount(mount);
+		if (this.startup != null) {
+			save.setStartup(this.startup);
+		}
+
+		for (String[] overlay : this.mounts) {
+			FileMount directory = new FileMount(new File(overlay[1]), Long.MAX_VALUE);
+			save.addMount(overlay[0], directory, overlay[2].equals("ro"));
+		}
+
+		return save;
 	}
 
 	@Override
@@ -208,13 +971,7 @@ public class Minion implements IComputerEnvironment {
 		//  path: lua/rom
 
 		try {
-			String root = Computer.class.getProtectionDomain().getCodeSource()
-				.getLocation().getPath().replace("%20", " ");
-			if (root.indexOf("!") != -1) {
-				root = root.substring(0, root.indexOf("!"));
-			}
-
-			File location = new File(root);
+			File location = Compat.getJar();
 
 			// Add the ROM folder
 			ArrayList<IMount> mounts = new ArrayList<IMount>();
@@ -222,10 +979,19 @@ public class Minion implements IComputerEnvironment {
 			IMount jarMount = new JarMount(location, assets);
 			mounts.add(jarMount);
 
-			// Add additional programs folder
-			File additional = new File(romDirectory);
-			if (additional.exists() && additional.isDirectory()) {
-				mounts.add(new FileMount(additional, 0L));
+			// Add the global ROM folder, then each layer on top, with later
+			// layers taking priority
+			List<String> layers = new ArrayList<String>();
+			if (this.globalRom) {
+				layers.add(this.romDirectory);
+			}
+
+			layers.addAll(this.romLayers);
+			for (String layer : layers) {
+				File additional = new File(layer);
+				if (additional.exists() && additional.isDirectory()) {
+					mounts.add(new FileMount(additional, 0L));
+				}
 			}
 
 			if (mounts.size() > 1) {
@@ -4,6 +4,9 @@
  */
 
 
+import java.util.Map;
+import java.util.HashMap;
+import java.util.LinkedHashMap;
 import java.util.List;
 import java.util.ArrayList;
 
@@ -15,17 +18,36 @@ import dan200.computercraft.api.peripheral.IPeripheral;
 
 public class Modem implements IPeripheral, Receiver {
 
-	private static Network network = new Network();
-
-	private IComputerAccess attachedComputer;
+	private volatile IComputerAccess attachedComputer;
 	private List<Integer> openChannels;
 
+	private Minion computer;
+	private volatile Network network;
+	private Cable cable;
+	private boolean ender;
+	private Map<String, RemotePeripheral> remotePeripherals;
+
 	/*
-	 * Create a new modem peripheral.
+	 * Create a new wireless modem peripheral that isn't placed on a computer.
 	 */
 	public Modem() {
+		this(null, Network.get(Network.DEFAULT), null, false);
+	}
+
+	/*
+	 * Create a new modem peripheral placed on the given computer, which
+	 * determines its position. The modem is wired if connected to a cable,
+	 * otherwise it's a wireless modem on the given network, or an ender modem
+	 * with unlimited range.
+	 */
+	public Modem(Minion computer, Network network, Cable cable, boolean ender) {
 		this.attachedComputer = null;
 		this.openChannels = new ArrayList<Integer>();
+		this.computer = computer;
+		this.network = network;
+		this.cable = cable;
+		this.ender = ender;
+		this.remotePeripherals = new LinkedHashMap<String, RemotePeripheral>();
 	}
 
 	/*
@@ -44,23 +66,44 @@ public class Modem implements IPeripheral, Receiver {
 		return channel;
 	}
 
+	/*
+	 * Parses a string at the given index from the given set of arguments.
+	 */
+	private static String parseString(Object[] arguments, int index) throws LuaException {
+		if (arguments.length <= index || !(arguments[index] instanceof String)) {
+			throw new LuaException("Expected string");
+		}
+
+		return (String) arguments[index];
+	}
+
+	/*
+	 * Returns true if this is a wireless modem.
+	 */
+	public boolean isWireless() {
+		return this.cable == null;
+	}
+
 	/*
 	 * Returns true if the given channel is currently open.
 	 */
-	public boolean isOpen(int channel) {
+	public synchronized boolean isOpen(int channel) {
 		return this.openChannels.contains(new Integer(channel));
 	}
 
 	/*
 	 * Opens a channel for receiving.
 	 */
-	public void open(int channel) throws LuaException {
+	public synchronized void open(int channel) throws LuaException {
 		if (!this.isOpen(channel)) {
 			if (this.openChannels.size() >= 128) {
 				throw new LuaException("Too many open channels");
 			}
 
-			network.listen(this, channel);
+			if (this.isWireless()) {
+				this.network.listen(this, channel);
+			}
+
 			this.openChannels.add(new Integer(channel));
 		}
 	}
@@ -68,9 +111,12 @@ public class Modem implements IPeripheral, Receiver {
 	/*
 	 * Closes the channel so that no more messages will be received on it.
 	 */
-	public void close(int channel) {
+	public synchronized void close(int channel) {
 		if (this.isOpen(channel)) {
-			network.unlisten(this, channel);
+			if (this.isWireless()) {
+				this.network.unlisten(this, channel);
+			}
+
 			this.openChannels.remove(new Integer(channel));
 		}
 	}
@@ -78,34 +124,175 @@ public class Modem implements IPeripheral, Receiver {
 	/*
 	 * Closes all open channels.
 	 */
-	public void closeAll() {
-		for (Integer open : this.openChannels) {
-			network.unlisten(this, open);
+	public synchronized void closeAll() {
+		if (this.isWireless()) {
+			for (Integer open : this.openChannels) {
+				this.network.unlisten(this, open);
+			}
+		}
+
+		this.openChannels.clear();
+	}
+
+	/*
+	 * Moves a wireless modem to a different network, keeping its open channels.
+	 */
+	public synchronized void setNetwork(Network network) {
+		if (this.isWireless()) {
+			for (Integer open : this.openChannels) {
+				this.network.unlisten(this, open);
+				network.listen(this, open);
+			}
 		}
+
+		this.network = network;
 	}
 
 	/*
 	 * Transmits the given message over the given channel.
 	 */
 	public void transmit(Object message, int channel, int replyChannel) {
-		network.transmit(message, channel, replyChannel);
+		if (this.isWireless()) {
+			this.network.transmit(this, message, channel, replyChannel);
+		} else {
+			this.cable.transmit(this, message, channel, replyChannel);
+		}
 	}
 
 	/*
 	 * A callback triggered when a message is received on one of our open channels.
 	 */
-	public void receive(Object message, int channel, int replyChannel) {
-		if (this.attachedComputer != null && this.isOpen(channel)) {
-			this.attachedComputer.queueEvent("modem_message", new Object[] {
-				this.attachedComputer.getAttachmentName(),
+	public void receive(Object message, int channel, int replyChannel, double distance) {
+		IComputerAccess computer = this.attachedComputer;
+		if (computer != null && this.isOpen(channel)) {
+			computer.queueEvent("modem_message", new Object[] {
+				computer.getAttachmentName(),
 				new Integer(channel),
 				new Integer(replyChannel),
 				message,
-				new Integer(1),
+				new Double(distance),
 			});
 		}
 	}
 
+	/*
+	 * Returns the ID of the computer the modem is attached to, or -1.
+	 */
+	public int getComputerID() {
+		IComputerAccess computer = this.attachedComputer;
+		return computer == null ? -1 : computer.getID();
+	}
+
+	/*
+	 * Returns the ID of the computer and the side the modem is attached to.
+	 */
+	public String getName() {
+		IComputerAccess computer = this.attachedComputer;
+		if (computer == null) {
+			return "modem";
+		}
+
+		return "computer " + computer.getID() + " " + computer.getAttachmentName();
+	}
+
+	/*
+	 * Returns the position of the computer the modem is placed on.
+	 */
+	public double[] getPosition() {
+		if (this.computer == null) {
+			return new double[] {0.0, 0.0, 0.0};
+		}
+
+		return this.computer.getPosition();
+	}
+
+	/*
+	 * Returns how far the modem's messages travel.
+	 */
+	public double getRange() {
+		if (this.ender) {
+			return -1.0;
+		}
+
+		return Network.range(this.getPosition()[1]);
+	}
+
+
+
+	/*
+	 * Remote Peripherals
+	 */
+
+	/*
+	 * Makes a peripheral on the cable available to the attached computer.
+	 */
+	public synchronized void attachRemote(String name, IPeripheral peripheral) {
+		if (this.attachedComputer != null && !this.remotePeripherals.containsKey(name)) {
+			RemotePeripheral remote = new RemotePeripheral(name, peripheral,
+				this.attachedComputer);
+			this.remotePeripherals.put(name, remote);
+			peripheral.attach(remote);
+			this.attachedComputer.queueEvent("peripheral", new Object[] {name});
+		}
+	}
+
+	/*
+	 * Removes a peripheral on the cable from the attached computer.
+	 */
+	public synchronized void detachRemote(String name) {
+		RemotePeripheral remote = this.remotePeripherals.remove(name);
+		if (remote != null) {
+			remote.getPeripheral().detach(remote);
+			if (this.attachedComputer != null) {
+				this.attachedComputer.queueEvent("peripheral_detach", new Object[] {name});
+			}
+		}
+	}
+
+	/*
+	 * Returns the remote peripheral with the given name, or null.
+	 */
+	private synchronized RemotePeripheral getRemote(String name) {
+		return this.remotePeripherals.get(name);
+	}
+
+	/*
+	 * Returns a Lua table containing the names of all remote peripherals.
+	 */
+	private synchronized Map<Object, Object> getNamesRemote() {
+		Map<Object, Object> table = new HashMap<Object, Object>();
+		int index = 1;
+		for (String name : this.remotePeripherals.keySet()) {
+			table.put(new Integer(index), name);
+			index++;
+		}
+
+		return table;
+	}
+
+	/*
+	 * Calls a method on a remote peripheral.
+	 */
+	private Object[] callRemote(ILuaContext context, Object[] arguments)
+			throws LuaException, InterruptedException {
+		String name = Modem.parseString(arguments, 0);
+		String method = Modem.parseString(arguments, 1);
+
+		RemotePeripheral remote = this.getRemote(name);
+		if (remote == null) {
+			throw new LuaException("No peripheral attached");
+		}
+
+		int index = remote.methodIndex(method);
+		if (index < 0) {
+			throw new LuaException("No such method " + method);
+		}
+
+		Object[] remaining = new Object[arguments.length - 2];
+		System.arraycopy(arguments, 2, remaining, 0, remaining.length);
+		return remote.getPeripheral().callMethod(remote, context, index, remaining);
+	}
+
 
 	/*
 	 * Peripheral
@@ -118,7 +305,14 @@ public class Modem implements IPeripheral, Receiver {
 
 	@Override
 	public String[] getMethodNames() {
-		return new String[] {"open", "isOpen", "close", "closeAll", "transmit", "isWireless"};
+		if (this.isWireless()) {
+			return new String[] {"open", "isOpen", "close", "closeAll", "transmit",
+				"isWireless"};
+		}
+
+		return new String[] {"open", "isOpen", "close", "closeAll", "transmit",
+			"isWireless", "getNamesRemote", "isPresentRemote", "getTypeRemote",
+			"getMethodsRemote", "callRemote"};
 	}
 
 	@Override
@@ -147,7 +341,35 @@ public class Modem implements IPeripheral, Receiver {
 			this.transmit(message, channel, replyChannel);
 		} else if (method == 5) {
 			// Is wireless
-			return new Object[] {new Boolean(true)};
+			return new Object[] {new Boolean(this.isWireless())};
+		} else if (method == 6) {
+			// Get names remote
+			return new Object[] {this.getNamesRemote()};
+		} else if (method == 7) {
+			// Is present remote
+			String name = Modem.parseString(arguments, 0);
+			return new Object[] {new Boolean(this.getRemote(name) != null)};
+		} else if (method == 8) {
+			// Get type remote
+			RemotePeripheral remote = this.getRemote(Modem.parseString(arguments, 0));
+			if (remote != null) {
+				return new Object[] {remote.getPeripheral().getType()};
+			}
+		} else if (method == 9) {
+			// Get methods remote
+			RemotePeripheral remote = this.getRemote(Modem.parseString(arguments, 0));
+			if (remote != null) {
+				Map<Object, Object> table = new HashMap<Object, Object>();
+				String[] methods = remote.getPeripheral().getMethodNames();
+				for (int i = 0; i < methods.length; i++) {
+					table.put(new Integer(i + 1), methods[i]);
+				}
+
+				return new Object[] {table};
+			}
+		} else if (method == 10) {
+			// Call remote
+			return this.callRemote(context, arguments);
 		}
 
 		return new Object[] {};
@@ -156,10 +378,21 @@ public class Modem implements IPeripheral, Receiver {
 	@Override
 	public void attach(IComputerAccess computer) {
 		this.attachedComputer = computer;
+		if (!this.isWireless()) {
+			this.cable.connect(this);
+		}
 	}
 
 	@Override
 	public void detach(IComputerAccess computer) {
+		// Channels don't stay open once the modem is removed, or the computer
+		// shuts down or reboots
+		this.closeAll();
+
+		if (!this.isWireless()) {
+			this.cable.disconnect(this);
+		}
+
 		this.attachedComputer = null;
 	}
 
@@ -8,44 +8,316 @@ import java.util.Map;
 import java.util.HashMap;
 import java.util.List;
 import java.util.ArrayList;
+import java.util.LinkedList;
+import java.util.PriorityQueue;
+import java.util.concurrent.ConcurrentHashMap;
+import java.util.concurrent.ConcurrentMap;
+import java.util.concurrent.CopyOnWriteArrayList;
 
 
 public class Network {
 
-	private static Map<Integer, List<Receiver>> listeners =
-		new HashMap<Integer, List<Receiver>>();
+	/*
+	 * The height above which the range of wireless modems starts increasing.
+	 */
+	private static final double RANGE_BASE_HEIGHT = 96.0;
+
+	/*
+	 * The height at which wireless modems reach their maximum range.
+	 */
+	private static final double RANGE_MAX_HEIGHT = 255.0;
+
+	/*
+	 * The name of the network modems use unless told otherwise.
+	 */
+	public static final String DEFAULT = "default";
+
+	private static Map<String, Network> networks = new HashMap<String, Network>();
+
+	private static volatile double minRange = 64.0;
+	private static volatile double maxRange = 384.0;
+
+	private static PriorityQueue<Delayed> delayed = new PriorityQueue<Delayed>();
+	private static long delayedCount = 0;
+	private static double time = 0.0;
+	private static long tick = 0;
+
+	private String name;
+	private ConcurrentMap<Integer, List<Receiver>> listeners;
+	private List<Network> bridges;
+	private volatile Faults faults;
+
+	/*
+	 * Create a new, empty network.
+	 */
+	private Network(String name) {
+		this.name = name;
+		this.listeners = new ConcurrentHashMap<Integer, List<Receiver>>();
+		this.bridges = new ArrayList<Network>();
+		this.faults = null;
+	}
+
+	/*
+	 * Returns the network with the given name, creating it if needed.
+	 */
+	public static synchronized Network get(String name) {
+		Network network = networks.get(name);
+		if (network == null) {
+			network = new Network(name);
+			networks.put(name, network);
+		}
+
+		return network;
+	}
+
+	/*
+	 * Bridges two networks, so that messages sent on either are also heard on
+	 * the other.
+	 */
+	public static synchronized void bridge(Network a, Network b) {
+		if (a != b && !a.bridges.contains(b)) {
+			a.bridges.add(b);
+			b.bridges.add(a);
+		}
+	}
+
+	/*
+	 * Removes the bridge between two networks.
+	 */
+	public static synchronized void unbridge(Network a, Network b) {
+		a.bridges.remove(b);
+		b.bridges.remove(a);
+	}
+
+	/*
+	 * Returns this network and every network reachable from it over bridges.
+	 */
+	private static synchronized List<Network> reachable(Network start) {
+		List<Network> found = new ArrayList<Network>();
+		LinkedList<Network> queue = new LinkedList<Network>();
+		queue.add(start);
+
+		while (!queue.isEmpty()) {
+			Network network = queue.removeFirst();
+			if (!found.contains(network)) {
+				found.add(network);
+				queue.addAll(network.bridges);
+			}
+		}
+
+		return found;
+	}
+
+	/*
+	 * Returns the name of the network.
+	 */
+	public String getName() {
+		return this.name;
+	}
+
+	/*
+	 * Sets the faults applied to messages delivered on this network, or null
+	 * to deliver every message immediately.
+	 */
+	public void setFaults(Faults faults) {
+		this.faults = (faults == null || faults.isNone()) ? null : faults;
+	}
+
+	/*
+	 * Returns the faults applied to messages on this network, or null.
+	 */
+	public Faults getFaults() {
+		return this.faults;
+	}
+
+	/*
+	 * Sets the range of wireless modems at ground level and at the top of the
+	 * world.
+	 */
+	public static void setRange(double min, double max) {
+		minRange = min;
+		maxRange = max;
+	}
 
 	/*
-	 * Attach a receiver to be notified of messages on a particular channel
+	 * Returns the range of a wireless modem at the given height. Like in-game,
+	 * the range increases linearly with height above y = 96.
 	 */
-	public static void listen(Receiver receiver, int channel) {
-		if (listeners.containsKey(new Integer(channel))) {
-			listeners.get(new Integer(channel)).add(receiver);
-		} else {
-			List<Receiver> receivers = new ArrayList<Receiver>();
+	public static double range(double y) {
+		if (y <= RANGE_BASE_HEIGHT || maxRange <= minRange) {
+			return minRange;
+		}
+
+		double fraction = (y - RANGE_BASE_HEIGHT) / (RANGE_MAX_HEIGHT - RANGE_BASE_HEIGHT);
+		return Math.min(maxRange, minRange + fraction * (maxRange - minRange));
+	}
+
+	/*
+	 * Returns the distance between two receivers.
+	 */
+	public static double distance(Receiver a, Receiver b) {
+		double[] from = a.getPosition();
+		double[] to = b.getPosition();
+		double dx = to[0] - from[0];
+		double dy = to[1] - from[1];
+		double dz = to[2] - from[2];
+		return Math.sqrt(dx * dx + dy * dy + dz * dz);
+	}
+
+	/*
+	 * Attach a receiver to be notified of messages on a particular channel.
+	 * Safe to call from any computer's thread.
+	 */
+	public void listen(Receiver receiver, int channel) {
+		Integer key = new Integer(channel);
+		List<Receiver> receivers = this.listeners.get(key);
+		if (receivers == null) {
+			List<Receiver> created = new CopyOnWriteArrayList<Receiver>();
+			receivers = this.listeners.putIfAbsent(key, created);
+			if (receivers == null) {
+				receivers = created;
+			}
+		}
+
+		if (!receivers.contains(receiver)) {
 			receivers.add(receiver);
-			listeners.put(new Integer(channel), receivers);
 		}
 	}
 
 	/*
 	 * Remove a receiver from listening to the given channel.
 	 */
-	public static void unlisten(Receiver receiver, int channel) {
-		if (listeners.containsKey(new Integer(channel))) {
-			listeners.get(new Integer(channel)).remove(receiver);
+	public void unlisten(Receiver receiver, int channel) {
+		List<Receiver> receivers = this.listeners.get(new Integer(channel));
+		if (receivers != null) {
+			receivers.remove(receiver);
+		}
+	}
+
+	/*
+	 * Transmit a given message on the given channel to every other receiver
+	 * within range of the sender, on this network and any bridged to it.
+	 */
+	public void transmit(Receiver sender, Object message, int channel, int replyChannel) {
+		Sniffer.record(this.name, sender, message, channel, replyChannel);
+
+		for (Network network : reachable(this)) {
+			network.deliver(sender, message, channel, replyChannel);
+		}
+
+		Link.forward(this, sender, message, channel, replyChannel);
+	}
+
+	/*
+	 * Deliver a message to the receivers on this network only.
+	 */
+	private void deliver(Receiver sender, Object message, int channel, int replyChannel) {
+		List<Receiver> receivers = this.listeners.get(new Integer(channel));
+		if (receivers != null) {
+			for (Receiver receiver : receivers) {
+				if (receiver == sender) {
+					continue;
+				}
+
+				// Like in-game, a message arrives if it's within the range of
+				// either the sender or the receiver
+				double distance = distance(sender, receiver);
+				if (sender.getRange() < 0.0 || receiver.getRange() < 0.0 ||
+						distance <= Math.max(sender.getRange(), receiver.getRange())) {
+					this.deliverTo(receiver, Serializer.copy(message), channel, replyChannel,
+						distance);
+				}
+			}
+		}
+	}
+
+	/*
+	 * Deliver a message to a single receiver, applying the network's faults.
+	 */
+	private void deliverTo(Receiver receiver, Object message, int channel, int replyChannel,
+			double distance) {
+		Faults faults = this.faults;
+		if (faults == null) {
+			receiver.receive(message, channel, replyChannel, distance);
+			return;
+		}
+
+		if (faults.shouldDrop(receiver)) {
+			return;
+		}
+
+		int ticks = faults.nextDelay(receiver);
+		if (ticks == 0) {
+			receiver.receive(message, channel, replyChannel, distance);
+			return;
+		}
+
+		synchronized (delayed) {
+			delayed.add(new Delayed(tick + ticks, delayedCount, receiver, message, channel,
+				replyChannel, distance));
+			delayedCount++;
 		}
 	}
 
 	/*
-	 * Transmit a given message on the given channel.
+	 * Moves time on by the given number of seconds, delivering every delayed
+	 * message that is now due. Called once per frame, so delays are counted
+	 * in the same ticks as the computers.
 	 */
-	public static void transmit(Object message, int channel, int replyChannel) {
-		if (listeners.containsKey(new Integer(channel))) {
-			for (Receiver receiver : listeners.get(new Integer(channel))) {
-				receiver.receive(message, channel, replyChannel);
+	public static void advance(double delta) {
+		List<Delayed> due = new ArrayList<Delayed>();
+		synchronized (delayed) {
+			time += delta;
+			tick = (long) Math.floor(time * 20.0);
+
+			while (!delayed.isEmpty() && delayed.peek().due <= tick) {
+				due.add(delayed.poll());
 			}
 		}
+
+		for (Delayed message : due) {
+			message.receiver.receive(message.message, message.channel, message.replyChannel,
+				message.distance);
+		}
+	}
+
+
+	/*
+	 * A message held back by a network's faults.
+	 */
+	private static class Delayed implements Comparable<Delayed> {
+
+		public long due;
+		public long order;
+		public Receiver receiver;
+		public Object message;
+		public int channel;
+		public int replyChannel;
+		public double distance;
+
+		public Delayed(long due, long order, Receiver receiver, Object message, int channel,
+				int replyChannel, double distance) {
+			this.due = due;
+			this.order = order;
+			this.receiver = receiver;
+			this.message = message;
+			this.channel = channel;
+			this.replyChannel = replyChannel;
+			this.distance = distance;
+		}
+
+		/*
+		 * Messages are delivered in the order they're due, and in the order
+		 * they were sent if due at the same time.
+		 */
+		public int compareTo(Delayed other) {
+			if (this.due != other.due) {
+				return this.due < other.due ? -1 : 1;
+			}
+
+			return this.order < other.order ? -1 : (this.order == other.order ? 0 : 1);
+		}
+
 	}
 
 }
@@ -0,0 +1,152 @@
+/*
+ * Pocket
+ *
+ * The `pocket` API of pocket computers, swapping the upgrade on the back of
+ * the computer with those in its owner's inventory. There's no player, so the
+ * inventory is just a list of spare upgrades.
+ */
+
+
+import java.util.List;
+import java.util.ArrayList;
+
+import dan200.computercraft.api.lua.ILuaContext;
+import dan200.computercraft.api.lua.LuaException;
+import dan200.computercraft.core.apis.ILuaAPI;
+
+
+public class Pocket implements ILuaAPI {
+
+	/*
+	 * The upgrades a pocket computer can have.
+	 */
+	public static final String[] UPGRADES = new String[] {"modem", "ender modem", "speaker"};
+
+	private static final String[] METHODS = new String[] {"equipBack", "unequipBack"};
+
+	private Minion computer;
+	private String equipped;
+	private List<String> spares;
+
+	/*
+	 * Create the pocket API for a computer, with no upgrade equipped and the
+	 * given spare upgrades.
+	 */
+	public Pocket(Minion computer, List<String> spares) {
+		this.computer = computer;
+		this.equipped = null;
+		this.spares = new ArrayList<String>(spares);
+	}
+
+	/*
+	 * Returns true if the given name is an upgrade.
+	 */
+	public static boolean isUpgrade(String name) {
+		for (String upgrade : UPGRADES) {
+			if (upgrade.equals(name)) {
+				return true;
+			}
+		}
+
+		return false;
+	}
+
+	/*
+	 * Returns the upgrade equipped on the back, or null.
+	 */
+	public synchronized String getEquipped() {
+		return this.equipped;
+	}
+
+	/*
+	 * Equips an upgrade on the back, replacing any already there without
+	 * keeping it.
+	 */
+	public synchronized void setEquipped(String upgrade) {
+		this.equipped = upgrade;
+		this.computer.setBackUpgrade(upgrade);
+	}
+
+	/*
+	 * Equips an upgrade on the back, or removes the current one if the
+	 * upgrade is null, putting the current one back with the spares.
+	 */
+	public synchronized void swap(String upgrade) {
+		if (this.equipped != null) {
+			this.spares.add(this.equipped);
+		}
+
+		this.spares.remove(upgrade);
+		this.setEquipped(upgrade);
+	}
+
+	/*
+	 * Equips the first spare upgrade, putting the current one back with the
+	 * spares.
+	 */
+	private synchronized Object[] equipBack() {
+		if (this.spares.size() == 0) {
+			return new Object[] {Boolean.FALSE, "Cannot find a valid upgrade"};
+		}
+
+		String upgrade = this.spares.remove(0);
+		if (this.equipped != null) {
+			this.spares.add(this.equipped);
+		}
+
+		this.setEquipped(upgrade);
+		return new Object[] {Boolean.TRUE};
+	}
+
+	/*
+	 * Removes the current upgrade, putting it with the spares.
+	 */
+	private synchronized Object[] unequipBack() {
+		if (this.equipped == null) {
+			return new Object[] {Boolean.FALSE, "Nothing to unequip"};
+		}
+
+		this.spares.add(this.equipped);
+		this.setEquipped(null);
+		return new Object[] {Boolean.TRUE};
+	}
+
+
+	/*
+	 * Lua API
+	 */
+
+	@Override
+	public String[] getNames() {
+		return new String[] {"pocket"};
+	}
+
+	@Override
+	public void startup() {
+	}
+
+	@Override
+	public void advance(double delta) {
+	}
+
+	@Override
+	public void shutdown() {
+	}
+
+	@Override
+	public String[] getMethodNames() {
+		return METHODS;
+	}
+
+	@Override
+	public Object[] callMethod(ILuaContext context, int method, Object[] arguments)
+			throws LuaException, InterruptedException {
+		switch (method) {
+		case 0: return this.equipBack();
+		case 1: return this.unequipBack();
+		}
+
+		return new Object[] {};
+	}
+
+}
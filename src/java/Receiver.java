@@ -6,6 +6,23 @@
 
 public interface Receiver {
 
-	public void receive(Object message, int channel, int replyChannel);
+	/*
+	 * Returns the receiver's position in the world, as an x, y, z array.
+	 */
+	public double[] getPosition();
+
+	/*
+	 * Returns how far messages sent by the receiver travel, or a negative
+	 * number if they reach any distance.
+	 */
+	public double getRange();
+
+	/*
+	 * Returns a name for the receiver that's the same each run, such as
+	 * `computer 3 top`, so it gets the same sequence of faults each run.
+	 */
+	public String getName();
+
+	public void receive(Object message, int channel, int replyChannel, double distance);
 
 }
@@ -0,0 +1,85 @@
+/*
+ * Remote Peripheral
+ */
+
+
+import dan200.computercraft.api.filesystem.IMount;
+import dan200.computercraft.api.filesystem.IWritableMount;
+import dan200.computercraft.api.peripheral.IComputerAccess;
+import dan200.computercraft.api.peripheral.IPeripheral;
+
+
+public class RemotePeripheral implements IComputerAccess {
+
+	private String name;
+	private IPeripheral peripheral;
+	private IComputerAccess computer;
+
+	/*
+	 * Wraps a computer's access to a peripheral reached over a wired modem, so
+	 * that the peripheral sees its network name as its attachment name.
+	 */
+	public RemotePeripheral(String name, IPeripheral peripheral, IComputerAccess computer) {
+		this.name = name;
+		this.peripheral = peripheral;
+		this.computer = computer;
+	}
+
+	public String getName() {
+		return this.name;
+	}
+
+	public IPeripheral getPeripheral() {
+		return this.peripheral;
+	}
+
+	/*
+	 * Returns the index of the peripheral method with the given name, or -1.
+	 */
+	public int methodIndex(String method) {
+		String[] methods = this.peripheral.getMethodNames();
+		for (int i = 0; i < methods.length; i++) {
+			if (methods[i].equals(method)) {
+				return i;
+			}
+		}
+
+		return -1;
+	}
+
+
+	/*
+	 * Computer Access
+	 */
+
+	@Override
+	public String mount(String desiredLocation, IMount mount) {
+		return this.computer.mount(desiredLocation, mount);
+	}
+
+	@Override
+	public String mountWritable(String desiredLocation, IWritableMount mount) {
+		return this.computer.mountWritable(desiredLocation, mount);
+	}
+
+	@Override
+	public void unmount(String location) {
+		this.computer.unmount(location);
+	}
+
+	@Override
+	public int getID() {
+		return this.computer.getID();
+	}
+
+	@Override
+	public void queueEvent(String event, Object[] arguments) {
+		this.computer.queueEvent(event, arguments);
+	}
+
+	@Override
+	public String getAttachmentName() {
+		return this.name;
+	}
+
+}
@@ -0,0 +1,246 @@
+/*
+ * Save Mount
+ */
+
+
+import java.io.ByteArrayInputStream;
+import java.io.IOException;
+import java.io.InputStream;
+import java.io.OutputStream;
+import java.util.List;
+import java.util.ArrayList;
+
+import dan200.computercraft.api.filesystem.IWritableMount;
+
+
+public class SaveMount implements IWritableMount {
+
+	private static final String STARTUP = "startup";
+
+	private IWritableMount mount;
+	private byte[] startup;
+	private List<Overlay> overlays;
+
+	/*
+	 * Wraps a computer's save directory mount, so that a read only startup
+	 * program and other directories can be overlaid on it. The files in the
+	 * save directory are left untouched.
+	 */
+	public SaveMount(IWritableMount mount) {
+		this.mount = mount;
+		this.startup = null;
+		this.overlays = new ArrayList<Overlay>();
+	}
+
+	/*
+	 * Overlays a read only startup program with the given contents.
+	 */
+	public void setStartup(String startup) {
+		this.startup = startup.getBytes();
+	}
+
+	/*
+	 * Overlays a mount at a location, such as `lib` or `lib/extra`. Read only
+	 * mounts deny any changes to their files.
+	 */
+	public void addMount(String location, IWritableMount mount, boolean readOnly) {
+		this.overlays.add(new Overlay(location, mount, readOnly));
+	}
+
+	/*
+	 * Returns true if the path refers to the overlaid startup program.
+	 */
+	private boolean isStartup(String path) {
+		return this.startup != null && path.equals(STARTUP);
+	}
+
+	/*
+	 * Returns the innermost overlay containing a path, or null, so a mount at
+	 * `lib/extra` takes precedence over one at `lib` whichever was added first.
+	 */
+	private Overlay overlay(String path) {
+		Overlay result = null;
+		for (Overlay overlay : this.overlays) {
+			if (overlay.contains(path) &&
+					(result == null || overlay.location.length() > result.location.length())) {
+				result = overlay;
+			}
+		}
+
+		return result;
+	}
+
+	/*
+	 * Returns true if the path is a directory holding an overlay, but isn't
+	 * one itself.
+	 */
+	private boolean isOverlayParent(String path) {
+		for (Overlay overlay : this.overlays) {
+			if (path.length() == 0 || overlay.location.startsWith(path + "/")) {
+				return true;
+			}
+		}
+
+		return false;
+	}
+
+	/*
+	 * Returns the writable mount for a path, throwing if it's read only.
+	 */
+	private IWritableMount writable(String path) throws IOException {
+		Overlay overlay = this.overlay(path);
+		if (this.isStartup(path) || (overlay != null && overlay.readOnly) ||
+				(overlay != null && overlay.location.equals(path))) {
+			throw new IOException("Access denied");
+		}
+
+		return overlay == null ? this.mount : overlay.mount;
+	}
+
+	/*
+	 * Returns the path relative to the mount it's in.
+	 */
+	private String relative(String path) {
+		Overlay overlay = this.overlay(path);
+		return overlay == null ? path : overlay.relative(path);
+	}
+
+	@Override
+	public boolean exists(String path) throws IOException {
+		Overlay overlay = this.overlay(path);
+		if (overlay != null) {
+			return overlay.mount.exists(overlay.relative(path));
+		}
+
+		return this.isStartup(path) || (path.length() > 0 && this.isOverlayParent(path)) ||
+			this.mount.exists(path);
+	}
+
+	@Override
+	public boolean isDirectory(String path) throws IOException {
+		Overlay overlay = this.overlay(path);
+		if (overlay != null) {
+			return overlay.mount.isDirectory(overlay.relative(path));
+		}
+
+		return !this.isStartup(path) && (this.isOverlayParent(path) ||
+			this.mount.isDirectory(path));
+	}
+
+	@Override
+	public void list(String path, List<String> contents) throws IOException {
+		Overlay overlay = this.overlay(path);
+		if (overlay != null) {
+			overlay.mount.list(overlay.relative(path), contents);
+		} else if (this.mount.isDirectory(path)) {
+			this.mount.list(path, contents);
+		}
+
+		if (this.startup != null && path.length() == 0 && !contents.contains(STARTUP)) {
+			contents.add(STARTUP);
+		}
+
+		// Add the first component of each overlay below this directory,
+		// including overlays nested inside another
+		String prefix = path.length() == 0 ? "" : path + "/";
+		for (Overlay other : this.overlays) {
+			if (other.location.startsWith(prefix)) {
+				String name = other.location.substring(prefix.length()).split("/")[0];
+				if (!contents.contains(name)) {
+					contents.add(name);
+				}
+			}
+		}
+	}
+
+	@Override
+	public long getSize(String path) throws IOException {
+		if (this.isStartup(path)) {
+			return this.startup.length;
+		}
+
+		Overlay overlay = this.overlay(path);
+		if (overlay != null) {
+			return overlay.mount.getSize(overlay.relative(path));
+		}
+
+		return this.isOverlayParent(path) ? 0 : this.mount.getSize(path);
+	}
+
+	@Override
+	public InputStream openForRead(String path) throws IOException {
+		if (this.isStartup(path)) {
+			return new ByteArrayInputStream(this.startup);
+		}
+
+		Overlay overlay = this.overlay(path);
+		if (overlay != null) {
+			return overlay.mount.openForRead(overlay.relative(path));
+		}
+
+		return this.mount.openForRead(path);
+	}
+
+	@Override
+	public void makeDirectory(String path) throws IOException {
+		this.writable(path).makeDirectory(this.relative(path));
+	}
+
+	@Override
+	public void delete(String path) throws IOException {
+		if (this.isOverlayParent(path)) {
+			throw new IOException("Access denied");
+		}
+
+		this.writable(path).delete(this.relative(path));
+	}
+
+	@Override
+	public OutputStream openForWrite(String path) throws IOException {
+		return this.writable(path).openForWrite(this.relative(path));
+	}
+
+	@Override
+	public OutputStream openForAppend(String path) throws IOException {
+		return this.writable(path).openForAppend(this.relative(path));
+	}
+
+	@Override
+	public long getRemainingSpace() throws IOException {
+		return this.mount.getRemainingSpace();
+	}
+
+
+	/*
+	 * A mount overlaid at a location in the save directory.
+	 */
+	private static class Overlay {
+
+		public String location;
+		public IWritableMount mount;
+		public boolean readOnly;
+
+		public Overlay(String location, IWritableMount mount, boolean readOnly) {
+			this.location = location;
+			this.mount = mount;
+			this.readOnly = readOnly;
+		}
+
+		/*
+		 * Returns true if the path is the overlay's location or inside it.
+		 */
+		public boolean contains(String path) {
+			return path.equals(this.location) || path.startsWith(this.location + "/");
+		}
+
+		/*
+		 * Returns the path relative to the overlay's location.
+		 */
+		public String relative(String path) {
+			return path.length() == this.location.length() ? "" :
+				path.substring(this.location.length() + 1);
+		}
+
+	}
+
+}
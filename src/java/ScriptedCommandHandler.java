@@ -0,0 +1,82 @@
+/*
+ * Scripted Command Handler
+ *
+ * A command handler returning results set up ahead of time. A command is
+ * matched against the full commands scripted first, then against its name,
+ * the first word. Commands matching neither fail as unknown, unless a result
+ * is scripted for `*`.
+ */
+
+
+import java.util.Map;
+import java.util.HashMap;
+import java.util.Set;
+import java.util.TreeSet;
+
+
+public class ScriptedCommandHandler implements CommandHandler {
+
+	/*
+	 * The scripted command matching any command not otherwise scripted.
+	 */
+	public static final String ANY = "*";
+
+	private Map<String, CommandResult> results;
+
+	public ScriptedCommandHandler() {
+		this.results = new HashMap<String, CommandResult>();
+	}
+
+	/*
+	 * Sets the result of a command, either a full command such as
+	 * `time query daytime` or just a command name such as `say`.
+	 */
+	public synchronized void setResult(String command, CommandResult result) {
+		this.results.put(command.trim(), result);
+	}
+
+	/*
+	 * Removes every scripted result.
+	 */
+	public synchronized void clear() {
+		this.results.clear();
+	}
+
+	@Override
+	public synchronized CommandResult execute(int computer, String command) {
+		command = command.trim();
+		if (command.startsWith("/")) {
+			command = command.substring(1);
+		}
+
+		CommandResult result = this.results.get(command);
+		if (result == null) {
+			result = this.results.get(command.split("\\s+")[0]);
+		}
+
+		if (result == null) {
+			result = this.results.get(ANY);
+		}
+
+		if (result == null) {
+			result = new CommandResult(false, new String[] {
+				"Unknown command. Try /help for a list of commands",
+			});
+		}
+
+		return result;
+	}
+
+	@Override
+	public synchronized String[] list() {
+		Set<String> names = new TreeSet<String>();
+		for (String command : this.results.keySet()) {
+			if (!command.equals(ANY)) {
+				names.add(command.split("\\s+")[0]);
+			}
+		}
+
+		return names.toArray(new String[names.size()]);
+	}
+
+}
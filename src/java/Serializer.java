@@ -0,0 +1,304 @@
+/*
+ * Serializer
+ *
+ * Converts modem messages to and from text, using the same syntax as Lua
+ * table constructors, and copies messages between computers.
+ */
+
+
+import java.util.Map;
+import java.util.HashMap;
+import java.util.IdentityHashMap;
+
+
+public class Serializer {
+
+	/*
+	 * How deeply tables can be nested in a parsed message. Messages come from
+	 * other processes, so this stops a malicious one overflowing the stack.
+	 */
+	public static final int MAX_DEPTH = 128;
+
+	private String text;
+	private int position;
+	private int depth;
+
+	private Serializer(String text) {
+		this.text = text;
+		this.position = 0;
+		this.depth = 0;
+	}
+
+
+	/*
+	 * Copying
+	 */
+
+	/*
+	 * Returns a deep copy of a message, so that each computer receiving it gets
+	 * its own tables, like in-game. Only nil, booleans, numbers, strings and
+	 * tables can be sent; anything else becomes nil. Tables that refer to
+	 * themselves are copied with the same structure.
+	 */
+	public static Object copy(Object value) {
+		return copy(value, new IdentityHashMap<Object, Object>());
+	}
+
+	private static Object copy(Object value, IdentityHashMap<Object, Object> copies) {
+		if (value instanceof Boolean || value instanceof String) {
+			return value;
+		} else if (value instanceof Number) {
+			return new Double(((Number) value).doubleValue());
+		} else if (value instanceof Map) {
+			if (copies.containsKey(value)) {
+				return copies.get(value);
+			}
+
+			Map<Object, Object> table = new HashMap<Object, Object>();
+			copies.put(value, table);
+
+			for (Map.Entry<?, ?> entry : ((Map<?, ?>) value).entrySet()) {
+				Object key = copy(entry.getKey(), copies);
+				Object item = copy(entry.getValue(), copies);
+				if (key != null && item != null) {
+					table.put(key, item);
+				}
+			}
+
+			return table;
+		}
+
+		return null;
+	}
+
+
+	/*
+	 * Serializing
+	 */
+
+	/*
+	 * Serializes a message into a single line of text. Values that can't be
+	 * sent over a modem, and tables that contain themselves, become nil.
+	 */
+	public static String serialize(Object value) {
+		StringBuilder builder = new StringBuilder();
+		serialize(value, builder, new IdentityHashMap<Object, Object>());
+		return builder.toString();
+	}
+
+	private static void serialize(Object value, StringBuilder builder,
+			IdentityHashMap<Object, Object> ancestors) {
+		if (value instanceof Boolean) {
+			builder.append(((Boolean) value).booleanValue() ? "true" : "false");
+		} else if (value instanceof Number) {
+			builder.append(serializeNumber(((Number) value).doubleValue()));
+		} else if (value instanceof String) {
+			serializeString((String) value, builder);
+		} else if (value instanceof Map && !ancestors.containsKey(value)) {
+			ancestors.put(value, value);
+			builder.append("{");
+
+			boolean first = true;
+			for (Map.Entry<?, ?> entry : ((Map<?, ?>) value).entrySet()) {
+				if (!first) {
+					builder.append(",");
+				}
+
+				builder.append("[");
+				serialize(entry.getKey(), builder, ancestors);
+				builder.append("]=");
+				serialize(entry.getValue(), builder, ancestors);
+				first = false;
+			}
+
+			builder.append("}");
+			ancestors.remove(value);
+		} else {
+			builder.append("nil");
+		}
+	}
+
+	private static String serializeNumber(double number) {
+		if (Double.isNaN(number)) {
+			return "0/0";
+		} else if (Double.isInfinite(number)) {
+			return number > 0 ? "1/0" : "-1/0";
+		} else if (number == Math.floor(number) && Math.abs(number) < 1e15) {
+			return Long.toString((long) number);
+		}
+
+		return Double.toString(number);
+	}
+
+	private static void serializeString(String string, StringBuilder builder) {
+		builder.append("\"");
+		for (int i = 0; i < string.length(); i++) {
+			char character = string.charAt(i);
+			if (character == '"' || character == '\\') {
+				builder.append('\\').append(character);
+			} else if (character == '\n') {
+				builder.append("\\n");
+			} else if (character < 32 || character == 127) {
+				builder.append(String.format("\\%03d", (int) character));
+			} else {
+				builder.append(character);
+			}
+		}
+		builder.append("\"");
+	}
+
+
+	/*
+	 * Parsing
+	 */
+
+	/*
+	 * Parses a message serialized with `serialize`.
+	 */
+	public static Object parse(String text) throws IllegalArgumentException {
+		Serializer parser = new Serializer(text);
+		Object value = parser.parseValue();
+		parser.skipWhitespace();
+		if (parser.position != text.length()) {
+			throw parser.error("Unexpected trailing characters");
+		}
+
+		return value;
+	}
+
+	private IllegalArgumentException error(String message) {
+		return new IllegalArgumentException(message + " at character " + this.position);
+	}
+
+	private void skipWhitespace() {
+		while (this.position < this.text.length() &&
+				Character.isWhitespace(this.text.charAt(this.position))) {
+			this.position++;
+		}
+	}
+
+	private boolean consume(String expected) {
+		this.skipWhitespace();
+		if (this.text.startsWith(expected, this.position)) {
+			this.position += expected.length();
+			return true;
+		}
+
+		return false;
+	}
+
+	private void expect(String expected) {
+		if (!this.consume(expected)) {
+			throw this.error("Expected `" + expected + "`");
+		}
+	}
+
+	private Object parseValue() {
+		this.skipWhitespace();
+		if (this.position >= this.text.length()) {
+			throw this.error("Unexpected end of message");
+		}
+
+		char next = this.text.charAt(this.position);
+		if (this.consume("nil")) {
+			return null;
+		} else if (this.consume("true")) {
+			return Boolean.TRUE;
+		} else if (this.consume("false")) {
+			return Boolean.FALSE;
+		} else if (next == '"') {
+			return this.parseString();
+		} else if (next == '{') {
+			return this.parseTable();
+		}
+
+		return this.parseNumber();
+	}
+
+	private Double parseNumber() {
+		int start = this.position;
+		while (this.position < this.text.length() &&
+				"0123456789+-.eE/".indexOf(this.text.charAt(this.position)) >= 0) {
+			this.position++;
+		}
+
+		String number = this.text.substring(start, this.position);
+		if (number.equals("0/0")) {
+			return new Double(Double.NaN);
+		} else if (number.equals("1/0")) {
+			return new Double(Double.POSITIVE_INFINITY);
+		} else if (number.equals("-1/0")) {
+			return new Double(Double.NEGATIVE_INFINITY);
+		}
+
+		try {
+			return new Double(Double.parseDouble(number));
+		} catch (NumberFormatException e) {
+			this.position = start;
+			throw this.error("Invalid value");
+		}
+	}
+
+	private String parseString() {
+		StringBuilder builder = new StringBuilder();
+		this.position++;
+
+		while (this.position < this.text.length()) {
+			char character = this.text.charAt(this.position++);
+			if (character == '"') {
+				return builder.toString();
+			} else if (character != '\\') {
+				builder.append(character);
+			} else if (this.position < this.text.length()) {
+				char escaped = this.text.charAt(this.position++);
+				if (escaped == 'n') {
+					builder.append('\n');
+				} else if (Character.isDigit(escaped)) {
+					int start = this.position - 1;
+					while (this.position < this.text.length() && this.position - start < 3 &&
+							Character.isDigit(this.text.charAt(this.position))) {
+						this.position++;
+					}
+					builder.append((char) Integer.parseInt(this.text.substring(start,
+						this.position)));
+				} else {
+					builder.append(escaped);
+				}
+			}
+		}
+
+		throw this.error("Unfinished string");
+	}
+
+	private Map<Object, Object> parseTable() {
+		Map<Object, Object> table = new HashMap<Object, Object>();
+		this.expect("{");
+
+		this.depth++;
+		if (this.depth > MAX_DEPTH) {
+			throw this.error("Tables nested too deeply");
+		}
+
+		boolean first = true;
+		while (!this.consume("}")) {
+			if (!first) {
+				this.expect(",");
+			}
+			first = false;
+
+			this.expect("[");
+			Object key = this.parseValue();
+			this.expect("]");
+			this.expect("=");
+			Object value = this.parseValue();
+
+			if (key != null && value != null) {
+				table.put(key, value);
+			}
+		}
+
+		this.depth--;
+		return table;
+	}
+
+}
@@ -0,0 +1,166 @@
+/*
+ * Snapshots
+ *
+ * Copies of a computer's files at a point in time, which the computer can be
+ * rolled back to. Each snapshot is a numbered folder holding a full copy of
+ * the computer's save directory. Files are copied into a staging folder first
+ * and only moved into place once complete, so a failed copy never leaves a
+ * partial snapshot or partially restored files behind.
+ */
+
+
+import java.io.File;
+import java.io.IOException;
+import java.text.SimpleDateFormat;
+import java.util.Arrays;
+import java.util.Date;
+
+
+public class Snapshots {
+
+	private static SimpleDateFormat format = new SimpleDateFormat("yyyy-MM-dd HH:mm:ss");
+
+	/*
+	 * Copies a computer's files into a new snapshot in the given folder,
+	 * returning the snapshot's name.
+	 */
+	public static String take(File files, File snapshots) throws IOException {
+		String name = Integer.toString(latest(snapshots) + 1);
+		File snapshot = new File(snapshots, name);
+		File staging = new File(snapshots, name + ".partial");
+		try {
+			Archive.deleteTree(staging);
+			if (!staging.mkdirs()) {
+				throw new IOException("Failed to create " + staging.getPath());
+			}
+
+			if (files.isDirectory()) {
+				Archive.copyContents(files, staging);
+			}
+
+			if (!staging.renameTo(snapshot)) {
+				throw new IOException("Failed to create " + snapshot.getPath());
+			}
+		} catch (IOException e) {
+			Archive.deleteTree(staging);
+			throw e;
+		}
+
+		// Copying changes the folder's modification time, used as the time
+		// the snapshot was taken
+		snapshot.setLastModified(System.currentTimeMillis());
+		return name;
+	}
+
+	/*
+	 * Replaces a computer's files with those in a snapshot.
+	 */
+	public static void restore(File snapshot, File files) throws IOException {
+		if (!snapshot.isDirectory()) {
+			throw new IOException("No snapshot named " + snapshot.getName());
+		}
+
+		File staging = new File(files.getPath() + ".rollback");
+		try {
+			Archive.deleteTree(staging);
+			if (!staging.mkdirs()) {
+				throw new IOException("Failed to create " + staging.getPath());
+			}
+
+			Archive.copyContents(snapshot, staging);
+			Archive.replace(files, staging);
+		} catch (IOException e) {
+			Archive.deleteTree(staging);
+			throw e;
+		}
+	}
+
+	/*
+	 * Deletes a snapshot.
+	 */
+	public static void delete(File snapshot) throws IOException {
+		if (!snapshot.isDirectory()) {
+			throw new IOException("No snapshot named " + snapshot.getName());
+		}
+
+		Archive.deleteContents(snapshot);
+		if (!snapshot.delete()) {
+			throw new IOException("Failed to delete " + snapshot.getPath());
+		}
+	}
+
+	/*
+	 * Returns one line for each snapshot in a folder, oldest first, with tab
+	 * separated name, time taken and size in bytes.
+	 */
+	public static String list(File snapshots) {
+		int[] numbers = numbers(snapshots);
+		Arrays.sort(numbers);
+
+		StringBuilder result = new StringBuilder();
+		for (int number : numbers) {
+			File snapshot = new File(snapshots, Integer.toString(number));
+			result.append(number + "\t" + format.format(new Date(snapshot.lastModified())) +
+				"\t" + size(snapshot) + "\n");
+		}
+
+		return result.toString();
+	}
+
+	/*
+	 * Returns the number of the most recent snapshot in a folder, or 0 if
+	 * there are none.
+	 */
+	public static int latest(File snapshots) {
+		int latest = 0;
+		for (int number : numbers(snapshots)) {
+			latest = Math.max(latest, number);
+		}
+
+		return latest;
+	}
+
+	/*
+	 * Returns the numbers of the snapshots in a folder, ignoring anything
+	 * else in it.
+	 */
+	private static int[] numbers(File snapshots) {
+		String[] names = snapshots.list();
+		if (names == null) {
+			return new int[0];
+		}
+
+		int[] numbers = new int[names.length];
+		int count = 0;
+		for (String name : names) {
+			try {
+				int number = Integer.parseInt(name);
+				if (number > 0 && new File(snapshots, name).isDirectory()) {
+					numbers[count++] = number;
+				}
+			} catch (NumberFormatException e) {
+				// Not a snapshot
+			}
+		}
+
+		return Arrays.copyOf(numbers, count);
+	}
+
+	/*
+	 * Returns the total size of the files in a directory.
+	 */
+	private static long size(File directory) {
+		File[] children = directory.listFiles();
+		if (children == null) {
+			return 0;
+		}
+
+		long total = 0;
+		for (File child : children) {
+			total += child.isDirectory() ? size(child) : child.length();
+		}
+
+		return total;
+	}
+
+}
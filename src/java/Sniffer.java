@@ -0,0 +1,104 @@
+/*
+ * Sniffer
+ *
+ * Records every message transmitted by a modem, to a log file and optionally
+ * to the console.
+ */
+
+
+import java.io.File;
+import java.io.FileWriter;
+import java.io.IOException;
+import java.io.Writer;
+import java.text.SimpleDateFormat;
+import java.util.Date;
+
+
+public class Sniffer {
+
+	private static Writer log = null;
+	private static boolean console = false;
+	private static SimpleDateFormat format = new SimpleDateFormat("yyyy-MM-dd HH:mm:ss.SSS");
+
+	/*
+	 * Starts appending to the log file at the given path.
+	 */
+	public static synchronized void open(String path) {
+		close();
+
+		try {
+			File file = new File(path);
+			file.getParentFile().mkdirs();
+			log = new FileWriter(file, true);
+			log.write("# Started " + format.format(new Date()) + "\n");
+			log.write("# time\tnetwork\tsender\tchannel\treply channel\tmessage\n");
+			log.flush();
+		} catch (IOException e) {
+			System.out.println("Failed to open modem log " + path + ": " + e.getMessage());
+			log = null;
+		}
+	}
+
+	/*
+	 * Stops writing to the log file.
+	 */
+	public static synchronized void close() {
+		if (log != null) {
+			try {
+				log.close();
+			} catch (IOException e) {
+				// Nothing else we can do
+			}
+
+			log = null;
+		}
+	}
+
+	/*
+	 * Sets whether messages are printed to the console.
+	 */
+	public static synchronized void setConsole(boolean enabled) {
+		console = enabled;
+	}
+
+	/*
+	 * Returns whether messages are printed to the console.
+	 */
+	public static synchronized boolean isConsole() {
+		return console;
+	}
+
+	/*
+	 * Records a transmitted message. The sender is -1 if the message didn't
+	 * come from a computer in this process.
+	 */
+	public static synchronized void record(String network, Receiver sender, Object message,
+			int channel, int replyChannel) {
+		if (log == null && !console) {
+			return;
+		}
+
+		int id = -1;
+		if (sender instanceof Modem) {
+			id = ((Modem) sender).getComputerID();
+		}
+
+		String line = format.format(new Date()) + "\t" + network + "\t" + id + "\t" +
+			channel + "\t" + replyChannel + "\t" + Serializer.serialize(message);
+
+		if (console) {
+			System.out.println(line);
+		}
+
+		if (log != null) {
+			try {
+				log.write(line + "\n");
+				log.flush();
+			} catch (IOException e) {
+				System.out.println("Failed to write to modem log: " + e.getMessage());
+				close();
+			}
+		}
+	}
+
+}
@@ -0,0 +1,246 @@
+/*
+ * Speaker Peripheral
+ */
+
+
+import java.io.File;
+import java.io.FileWriter;
+import java.io.IOException;
+import java.util.List;
+import java.util.ArrayList;
+
+import dan200.computercraft.api.lua.ILuaContext;
+import dan200.computercraft.api.lua.LuaException;
+import dan200.computercraft.api.peripheral.IComputerAccess;
+import dan200.computercraft.api.peripheral.IPeripheral;
+
+
+public class Speaker implements IPeripheral {
+
+	/*
+	 * The maximum number of notes that can be played in a single tick.
+	 */
+	private static final int MAX_NOTES_PER_TICK = 8;
+
+	/*
+	 * How long the speaker has to be silent before the timeline is written.
+	 */
+	private static final double SAVE_DELAY = 1.0;
+
+	private IComputerAccess attachedComputer;
+	private String audioDirectory;
+	private String name;
+	private boolean live;
+
+	private List<Synthesizer.Event> timeline;
+	private double time;
+	private long lastTick;
+	private int notesThisTick;
+	private boolean unsaved;
+
+	/*
+	 * Create a new speaker peripheral, saving its audio to the given directory
+	 * under the given name.
+	 */
+	public Speaker(String audioDirectory, String name, boolean live) {
+		this.attachedComputer = null;
+		this.audioDirectory = audioDirectory;
+		this.name = name;
+		this.live = live;
+		this.timeline = new ArrayList<Synthesizer.Event>();
+		this.time = 0.0;
+		this.lastTick = -1;
+		this.notesThisTick = 0;
+		this.unsaved = false;
+
+		// Start a fresh timeline each time a speaker is created
+		new File(this.audioDirectory, this.name + ".txt").delete();
+	}
+
+	/*
+	 * Returns the time in seconds the speaker has been advanced by since it
+	 * was created.
+	 */
+	private synchronized double elapsed() {
+		return this.time;
+	}
+
+	/*
+	 * Parses an optional number argument, returning the default if absent.
+	 */
+	private static double parseNumber(Object[] arguments, int index, double fallback,
+			double min, double max) throws LuaException {
+		if (arguments.length <= index || arguments[index] == null) {
+			return fallback;
+		}
+
+		if (!(arguments[index] instanceof Double)) {
+			throw new LuaException("Expected number");
+		}
+
+		double value = ((Double) arguments[index]).doubleValue();
+		if (value < min || value > max) {
+			throw new LuaException("Expected number in range " + min + "-" + max);
+		}
+
+		return value;
+	}
+
+	/*
+	 * Parses a string argument at the given index.
+	 */
+	private static String parseString(Object[] arguments, int index) throws LuaException {
+		if (arguments.length <= index || !(arguments[index] instanceof String)) {
+			throw new LuaException("Expected string");
+		}
+
+		return (String) arguments[index];
+	}
+
+	/*
+	 * Adds an event to the timeline, returning false if too many sounds have
+	 * been played this tick.
+	 */
+	private synchronized boolean play(String instrument, double volume, double pitch,
+			boolean note) {
+		double time = this.elapsed();
+		long tick = (long) Math.floor(time * 20.0);
+
+		if (tick != this.lastTick) {
+			this.lastTick = tick;
+			this.notesThisTick = 0;
+		}
+
+		if (this.notesThisTick >= MAX_NOTES_PER_TICK) {
+			return false;
+		}
+
+		this.notesThisTick++;
+
+		Synthesizer.Event event = new Synthesizer.Event(tick / 20.0, instrument, volume,
+			pitch, note);
+		this.timeline.add(event);
+		this.unsaved = true;
+		this.log(tick, event);
+
+		if (this.live) {
+			Synthesizer.play(event);
+		}
+
+		return true;
+	}
+
+	/*
+	 * Appends an event to the speaker's timeline file.
+	 */
+	private void log(long tick, Synthesizer.Event event) {
+		File file = new File(this.audioDirectory, this.name + ".txt");
+
+		try {
+			file.getParentFile().mkdirs();
+			FileWriter writer = new FileWriter(file, true);
+			writer.write(tick + "\t" + (event.note ? "note" : "sound") + "\t" +
+				event.instrument + "\t" + event.volume + "\t" + event.pitch + "\n");
+			writer.close();
+		} catch (IOException e) {
+			e.printStackTrace();
+		}
+	}
+
+	/*
+	 * Writes the timeline rendered so far to a WAV file.
+	 */
+	public synchronized void save() {
+		if (!this.unsaved) {
+			return;
+		}
+
+		File file = new File(this.audioDirectory, this.name + ".wav");
+
+		try {
+			file.getParentFile().mkdirs();
+			Synthesizer.write(this.timeline, file);
+			this.unsaved = false;
+		} catch (IOException e) {
+			e.printStackTrace();
+		}
+	}
+
+	/*
+	 * Moves the speaker's timeline on by the given number of seconds, saving
+	 * it once the speaker has gone quiet. Called once per frame with the
+	 * same delta as the computer it's attached to, so notes are placed on
+	 * the computer's ticks rather than the wall clock.
+	 */
+	public synchronized void advance(double delta) {
+		this.time += delta;
+
+		if (this.unsaved && this.time - this.lastTick / 20.0 >= SAVE_DELAY) {
+			this.save();
+		}
+	}
+
+	/*
+	 * Writes out anything not yet saved, so the WAV file is complete. Called
+	 * when the speaker is removed or Mimic exits.
+	 */
+	public void close() {
+		this.save();
+	}
+
+
+	/*
+	 * Peripheral
+	 */
+
+	@Override
+	public String getType() {
+		return "speaker";
+	}
+
+	@Override
+	public String[] getMethodNames() {
+		return new String[] {"playNote", "playSound"};
+	}
+
+	@Override
+	public Object[] callMethod(IComputerAccess computer, ILuaContext context, int method,
+			Object[] arguments) throws LuaException, InterruptedException {
+		if (method == 0) {
+			// Play note
+			String instrument = Speaker.parseString(arguments, 0);
+			if (!Synthesizer.isInstrument(instrument)) {
+				throw new LuaException("Invalid instrument, \"" + instrument + "\"!");
+			}
+
+			double volume = Speaker.parseNumber(arguments, 1, 1.0, 0.0, 3.0);
+			double pitch = Speaker.parseNumber(arguments, 2, 1.0, 0.0, 24.0);
+			return new Object[] {new Boolean(this.play(instrument, volume, pitch, true))};
+		} else if (method == 1) {
+			// Play sound
+			String sound = Speaker.parseString(arguments, 0);
+			double volume = Speaker.parseNumber(arguments, 1, 1.0, 0.0, 3.0);
+			double speed = Speaker.parseNumber(arguments, 2, 1.0, 0.5, 2.0);
+			return new Object[] {new Boolean(this.play(sound, volume, speed, false))};
+		}
+
+		return new Object[] {};
+	}
+
+	@Override
+	public void attach(IComputerAccess computer) {
+		this.attachedComputer = computer;
+	}
+
+	@Override
+	public void detach(IComputerAccess computer) {
+		this.attachedComputer = null;
+		this.save();
+	}
+
+	@Override
+	public boolean equals(IPeripheral peripheral) {
+		return peripheral.getType() == this.getType();
+	}
+
+}
@@ -0,0 +1,60 @@
+/*
+ * Subscriber
+ *
+ * Listens for modem messages on a network on behalf of the host, so they can
+ * be read from the console or control socket.
+ */
+
+
+import java.util.Queue;
+
+
+public class Subscriber implements Receiver {
+
+	private Network network;
+	private Queue<String> received;
+
+	/*
+	 * Create a subscriber that adds messages heard on the given network to the
+	 * given queue.
+	 */
+	public Subscriber(Network network, Queue<String> received) {
+		this.network = network;
+		this.received = received;
+	}
+
+	/*
+	 * Returns the network the subscriber listens on.
+	 */
+	public Network getNetwork() {
+		return this.network;
+	}
+
+	/*
+	 * The host sits at the origin.
+	 */
+	public double[] getPosition() {
+		return new double[] {0.0, 0.0, 0.0};
+	}
+
+	/*
+	 * The host hears and is heard by every modem, regardless of distance.
+	 */
+	public double getRange() {
+		return -1.0;
+	}
+
+	public String getName() {
+		return "host";
+	}
+
+	/*
+	 * Records a received message as a tab separated line of network, channel,
+	 * reply channel, distance, and the serialized message.
+	 */
+	public void receive(Object message, int channel, int replyChannel, double distance) {
+		this.received.add(this.network.getName() + "\t" + channel + "\t" + replyChannel +
+			"\t" + distance + "\t" + Serializer.serialize(message));
+	}
+
+}
@@ -0,0 +1,246 @@
+/*
+ * Synthesizer
+ */
+
+
+import java.io.ByteArrayInputStream;
+import java.io.File;
+import java.io.IOException;
+import java.util.Collections;
+import java.util.List;
+import java.util.Random;
+
+import javax.sound.sampled.AudioFileFormat;
+import javax.sound.sampled.AudioFormat;
+import javax.sound.sampled.AudioInputStream;
+import javax.sound.sampled.AudioSystem;
+import javax.sound.sampled.Clip;
+import javax.sound.sampled.LineEvent;
+import javax.sound.sampled.LineListener;
+
+
+public class Synthesizer {
+
+	public static final int SAMPLE_RATE = 22050;
+
+	/*
+	 * The length of a single rendered note, in seconds.
+	 */
+	public static final double NOTE_LENGTH = 1.0;
+
+	/*
+	 * The instruments accepted by `playNote`, in note block order.
+	 */
+	public static final String[] INSTRUMENTS = new String[] {
+		"harp", "basedrum", "snare", "hat", "bass", "flute", "bell", "guitar",
+		"chime", "xylophone", "iron_xylophone", "cow_bell", "didgeridoo", "bit",
+		"banjo", "pling",
+	};
+
+	private static final AudioFormat FORMAT =
+		new AudioFormat((float) SAMPLE_RATE, 16, 1, true, false);
+
+	private static boolean liveAvailable = true;
+
+	/*
+	 * A single sound placed on a timeline.
+	 */
+	public static class Event {
+
+		public double time;
+		public String instrument;
+		public double volume;
+		public double pitch;
+		public boolean note;
+
+		public Event(double time, String instrument, double volume, double pitch, boolean note) {
+			this.time = time;
+			this.instrument = instrument;
+			this.volume = volume;
+			this.pitch = pitch;
+			this.note = note;
+		}
+
+	}
+
+	/*
+	 * Returns true if the given name is a valid note block instrument.
+	 */
+	public static boolean isInstrument(String name) {
+		for (String instrument : INSTRUMENTS) {
+			if (instrument.equals(name)) {
+				return true;
+			}
+		}
+
+		return false;
+	}
+
+	/*
+	 * Returns the frequency in hertz of a note block pitch (0-24) played on
+	 * the given instrument.
+	 */
+	private static double frequency(String instrument, double pitch) {
+		// Pitch 12 is F#4 on the harp
+		double frequency = 369.994 * Math.pow(2.0, (pitch - 12.0) / 12.0);
+
+		if (instrument.equals("bass") || instrument.equals("didgeridoo")) {
+			frequency /= 4.0;
+		} else if (instrument.equals("guitar")) {
+			frequency /= 2.0;
+		} else if (instrument.equals("flute") || instrument.equals("cow_bell")) {
+			frequency *= 2.0;
+		} else if (instrument.equals("bell") || instrument.equals("chime") ||
+				instrument.equals("xylophone")) {
+			frequency *= 4.0;
+		}
+
+		return frequency;
+	}
+
+	/*
+	 * Returns the value of the instrument's waveform at the given phase, where
+	 * a phase of 1 is one full period.
+	 */
+	private static double waveform(String instrument, double phase, Random noise) {
+		double t = phase - Math.floor(phase);
+
+		if (instrument.equals("snare") || instrument.equals("hat")) {
+			return noise.nextDouble() * 2.0 - 1.0;
+		} else if (instrument.equals("bit") || instrument.equals("iron_xylophone") ||
+				instrument.equals("cow_bell")) {
+			return t < 0.5 ? 0.6 : -0.6;
+		} else if (instrument.equals("guitar") || instrument.equals("banjo") ||
+				instrument.equals("didgeridoo")) {
+			return (t * 2.0 - 1.0) * 0.7;
+		} else if (instrument.equals("flute")) {
+			return 1.0 - 4.0 * Math.abs(t - 0.5);
+		} else if (instrument.equals("pling")) {
+			return 0.7 * Math.sin(2.0 * Math.PI * t) + 0.3 * Math.sin(4.0 * Math.PI * t);
+		}
+
+		return Math.sin(2.0 * Math.PI * t);
+	}
+
+	/*
+	 * Returns how quickly an instrument's volume decays.
+	 */
+	private static double decay(String instrument) {
+		if (instrument.equals("hat") || instrument.equals("xylophone")) {
+			return 18.0;
+		} else if (instrument.equals("snare") || instrument.equals("basedrum")) {
+			return 12.0;
+		} else if (instrument.equals("flute") || instrument.equals("didgeridoo")) {
+			return 2.0;
+		}
+
+		return 4.0;
+	}
+
+	/*
+	 * Mixes a single event into the given sample buffer.
+	 */
+	private static void mix(float[] samples, Event event, double offset) {
+		Random noise = new Random(Math.round(event.time * SAMPLE_RATE));
+		int start = (int) Math.round((event.time - offset) * SAMPLE_RATE);
+		int length = (int) (NOTE_LENGTH * SAMPLE_RATE);
+		double amplitude = Math.min(Math.max(event.volume, 0.0), 3.0) / 3.0 * 0.5;
+
+		double frequency;
+		double decay;
+		if (event.note) {
+			frequency = frequency(event.instrument, event.pitch);
+			decay = decay(event.instrument);
+		} else {
+			// There are no sound assets available, so named sounds are
+			// rendered as a short burst of noise at the requested speed
+			frequency = 220.0 * event.pitch;
+			decay = 10.0 / event.pitch;
+		}
+
+		for (int i = 0; i < length && start + i < samples.length; i++) {
+			if (start + i < 0) {
+				continue;
+			}
+
+			double time = (double) i / SAMPLE_RATE;
+			double phase = time * frequency;
+			if (event.instrument.equals("basedrum")) {
+				// Sweep the kick drum downwards
+				phase = time * frequency * 0.5 * Math.exp(-time * 8.0);
+			}
+
+			double value = event.note ?
+				waveform(event.instrument, phase, noise) :
+				waveform("snare", phase, noise);
+			samples[start + i] += (float) (value * amplitude * Math.exp(-time * decay));
+		}
+	}
+
+	/*
+	 * Renders a list of events into 16 bit little endian PCM data, with the
+	 * first sample at the given time offset.
+	 */
+	public static byte[] render(List<Event> events, double offset, double duration) {
+		float[] samples = new float[(int) Math.ceil(duration * SAMPLE_RATE)];
+		for (Event event : events) {
+			mix(samples, event, offset);
+		}
+
+		byte[] data = new byte[samples.length * 2];
+		for (int i = 0; i < samples.length; i++) {
+			float clamped = Math.max(-1.0f, Math.min(1.0f, samples[i]));
+			short value = (short) (clamped * Short.MAX_VALUE);
+			data[i * 2] = (byte) (value & 0xff);
+			data[i * 2 + 1] = (byte) ((value >> 8) & 0xff);
+		}
+
+		return data;
+	}
+
+	/*
+	 * Renders an entire timeline to a WAV file.
+	 */
+	public static void write(List<Event> events, File file) throws IOException {
+		double duration = NOTE_LENGTH;
+		for (Event event : events) {
+			duration = Math.max(duration, event.time + NOTE_LENGTH);
+		}
+
+		byte[] data = render(events, 0.0, duration);
+		AudioInputStream stream = new AudioInputStream(new ByteArrayInputStream(data),
+			FORMAT, data.length / FORMAT.getFrameSize());
+		AudioSystem.write(stream, AudioFileFormat.Type.WAVE, file);
+	}
+
+	/*
+	 * Plays a single event through the default audio device. If no audio
+	 * device is available, live playback is disabled for the rest of the run.
+	 */
+	public static void play(Event event) {
+		if (!liveAvailable) {
+			return;
+		}
+
+		Event immediate = new Event(0.0, event.instrument, event.volume, event.pitch,
+			event.note);
+		byte[] data = render(Collections.singletonList(immediate), 0.0, NOTE_LENGTH);
+
+		try {
+			final Clip clip = AudioSystem.getClip();
+			clip.addLineListener(new LineListener() {
+				public void update(LineEvent lineEvent) {
+					if (lineEvent.getType() == LineEvent.Type.STOP) {
+						clip.close();
+					}
+				}
+			});
+			clip.open(FORMAT, data, 0, data.length);
+			clip.start();
+		} catch (Exception e) {
+			// Headless, or no audio device
+			liveAvailable = false;
+		}
+	}
+
+}
@@ -0,0 +1,666 @@
+/*
+ * Turtle
+ *
+ * The `turtle` API, moving a computer around the voxel world. Like in-game,
+ * each command yields until a `turtle_response` event, so other events
+ * arriving while a command runs are discarded.
+ */
+
+
+import java.util.Map;
+import java.util.HashMap;
+
+import dan200.computercraft.api.lua.ILuaContext;
+import dan200.computercraft.api.lua.LuaException;
+import dan200.computercraft.core.apis.ILuaAPI;
+
+
+public class Turtle implements ILuaAPI {
+
+	/*
+	 * The number of inventory slots.
+	 */
+	public static final int SLOTS = 16;
+
+	/*
+	 * The most items that fit in a single slot.
+	 */
+	public static final int STACK_SIZE = 64;
+
+	/*
+	 * The names of each direction a turtle can face, and the change in x and
+	 * z moving forward in that direction.
+	 */
+	public static final String[] DIRECTIONS = new String[] {"north", "east", "south", "west"};
+	private static final char[] ARROWS = new char[] {'^', '>', 'v', '<'};
+	private static final int[][] OFFSETS = new int[][] {{0, -1}, {1, 0}, {0, 1}, {-1, 0}};
+
+	/*
+	 * How much fuel items give when burnt, as pairs of item name and fuel.
+	 */
+	private static final Object[][] FUEL_VALUES = new Object[][] {
+		{"minecraft:coal", new Integer(80)},
+		{"minecraft:coal_block", new Integer(800)},
+		{"minecraft:lava_bucket", new Integer(1000)},
+		{"minecraft:log", new Integer(15)},
+		{"minecraft:planks", new Integer(15)},
+		{"minecraft:stick", new Integer(5)},
+	};
+
+	private static final String[] METHODS = new String[] {
+		"forward", "back", "up", "down", "turnLeft", "turnRight",
+		"dig", "digUp", "digDown", "place", "placeUp", "placeDown",
+		"detect", "detectUp", "detectDown", "inspect", "inspectUp", "inspectDown",
+		"compare", "compareUp", "compareDown",
+		"select", "getSelectedSlot", "getItemCount", "getItemSpace", "getItemDetail",
+		"transferTo", "drop", "dropUp", "dropDown",
+		"getFuelLevel", "getFuelLimit", "refuel",
+		"attack", "attackUp", "attackDown", "suck", "suckUp", "suckDown",
+		"equipLeft", "equipRight",
+	};
+
+	private Minion computer;
+	private World world;
+
+	private int direction;
+	private String[] items;
+	private int[] counts;
+	private int selected;
+
+	private boolean needsFuel;
+	private int fuel;
+	private int fuelLimit;
+
+	private int commandID;
+
+	/*
+	 * Create the turtle API for a computer, facing north at the computer's
+	 * position in the given world.
+	 */
+	public Turtle(Minion computer, World world, boolean needsFuel) {
+		this.computer = computer;
+		this.world = world;
+		this.direction = 0;
+		this.items = new String[SLOTS];
+		this.counts = new int[SLOTS];
+		this.selected = 0;
+		this.needsFuel = needsFuel;
+		this.fuel = 0;
+		this.fuelLimit = computer.advanced ? 100000 : 20000;
+		this.commandID = 0;
+	}
+
+
+	/*
+	 * State
+	 */
+
+	/*
+	 * Returns the block the turtle is in.
+	 */
+	public int[] getBlockPosition() {
+		double[] position = this.computer.getPosition();
+		return new int[] {
+			(int) Math.floor(position[0]),
+			(int) Math.floor(position[1]),
+			(int) Math.floor(position[2]),
+		};
+	}
+
+	public void setBlockPosition(int x, int y, int z) {
+		this.computer.setPosition(x, y, z);
+	}
+
+	/*
+	 * Returns the name of the direction the turtle is facing.
+	 */
+	public String getDirection() {
+		return DIRECTIONS[this.direction];
+	}
+
+	public int getID() {
+		return this.computer.id;
+	}
+
+	public synchronized int getFuelLevel() {
+		return this.fuel;
+	}
+
+	public synchronized void setFuelLevel(int fuel) {
+		this.fuel = Math.max(0, Math.min(this.fuelLimit, fuel));
+	}
+
+	public synchronized int getSelectedSlot() {
+		return this.selected;
+	}
+
+	/*
+	 * Returns the name of the item in a slot, from 0 to 15, or null if empty.
+	 */
+	public synchronized String getItem(int slot) {
+		return this.items[slot];
+	}
+
+	public synchronized int getItemCount(int slot) {
+		return this.counts[slot];
+	}
+
+	/*
+	 * Puts items in a slot, from 0 to 15, replacing what was there.
+	 */
+	public synchronized void setItem(int slot, String name, int count) {
+		count = Math.max(0, Math.min(STACK_SIZE, count));
+		this.items[slot] = count == 0 ? null : name;
+		this.counts[slot] = this.items[slot] == null ? 0 : count;
+	}
+
+	/*
+	 * Returns the fuel level as shown to Lua, which may be `unlimited`.
+	 */
+	public String describeFuel() {
+		return this.needsFuel ? Integer.toString(this.getFuelLevel()) : "unlimited";
+	}
+
+	/*
+	 * Draws a top down view of the world around the turtle, at the height
+	 * it's at, with north up. Air above a block is drawn as a dim dot.
+	 * Returns a line for each row, each containing the row's text, text
+	 * colors and background colors separated by tabs, with colors as hex
+	 * digits.
+	 */
+	public String renderSlice(int width, int height) {
+		int[] center = this.getBlockPosition();
+		int left = center[0] - width / 2;
+		int top = center[2] - height / 2;
+		int y = center[1];
+
+		StringBuilder builder = new StringBuilder();
+		for (int row = 0; row < height; row++) {
+			StringBuilder text = new StringBuilder();
+			StringBuilder foreground = new StringBuilder();
+			StringBuilder background = new StringBuilder();
+
+			for (int column = 0; column < width; column++) {
+				int x = left + column;
+				int z = top + row;
+
+				Turtle turtle = this.world.getTurtle(x, y, z);
+				String block = this.world.getBlock(x, y, z);
+				if (turtle != null) {
+					text.append(ARROWS[turtle.direction]);
+					foreground.append(turtle == this ? '4' : '0');
+					background.append('f');
+				} else if (block != null) {
+					String symbol = World.symbol(block);
+					text.append(symbol.charAt(0));
+					foreground.append(symbol.charAt(1));
+					background.append(symbol.charAt(2));
+				} else if (this.world.getBlock(x, y - 1, z) != null) {
+					text.append('.');
+					foreground.append('7');
+					background.append('f');
+				} else {
+					text.append(' ');
+					foreground.append('0');
+					background.append('f');
+				}
+			}
+
+			builder.append(text).append('\t').append(foreground).append('\t')
+				.append(background).append('\n');
+		}
+
+		return builder.toString();
+	}
+
+
+	/*
+	 * Helpers
+	 */
+
+	/*
+	 * Returns the block in front of, above, or below the turtle, for a
+	 * vertical direction of 0, 1 or -1.
+	 */
+	private int[] target(int vertical) {
+		int[] position = this.getBlockPosition();
+		if (vertical != 0) {
+			return new int[] {position[0], position[1] + vertical, position[2]};
+		}
+
+		int[] offset = OFFSETS[this.direction];
+		return new int[] {position[0] + offset[0], position[1], position[2] + offset[1]};
+	}
+
+	/*
+	 * Returns the fuel given by burning one of the given item, or 0.
+	 */
+	private static int fuelValue(String item) {
+		for (Object[] pair : FUEL_VALUES) {
+			if (pair[0].equals(item)) {
+				return ((Integer) pair[1]).intValue();
+			}
+		}
+
+		return 0;
+	}
+
+	/*
+	 * Adds items to the inventory, starting at the selected slot, returning
+	 * the number that didn't fit.
+	 */
+	private synchronized int store(String item, int count) {
+		for (int i = 0; i < SLOTS && count > 0; i++) {
+			int slot = (this.selected + i) % SLOTS;
+			if (this.items[slot] == null || this.items[slot].equals(item)) {
+				int moved = Math.min(count, STACK_SIZE - this.counts[slot]);
+				if (moved > 0) {
+					this.items[slot] = item;
+					this.counts[slot] += moved;
+					count -= moved;
+				}
+			}
+		}
+
+		return count;
+	}
+
+	/*
+	 * Removes up to the given number of items from a slot, returning the
+	 * number removed.
+	 */
+	private synchronized int take(int slot, int count) {
+		int taken = Math.min(count, this.counts[slot]);
+		this.counts[slot] -= taken;
+		if (this.counts[slot] == 0) {
+			this.items[slot] = null;
+		}
+
+		return taken;
+	}
+
+	private static Object[] success() {
+		return new Object[] {Boolean.TRUE};
+	}
+
+	private static Object[] failure(String message) {
+		return new Object[] {Boolean.FALSE, message};
+	}
+
+	/*
+	 * Parses a slot from 1 to 16 at the given index, returning it from 0 to
+	 * 15, or the fallback if the argument is absent.
+	 */
+	private static int parseSlot(Object[] arguments, int index, int fallback)
+			throws LuaException {
+		if (arguments.length <= index || arguments[index] == null) {
+			return fallback;
+		}
+
+		if (!(arguments[index] instanceof Double)) {
+			throw new LuaException("Expected number");
+		}
+
+		int slot = (int) ((Double) arguments[index]).doubleValue();
+		if (slot < 1 || slot > SLOTS) {
+			throw new LuaException("Slot number " + slot + " out of range");
+		}
+
+		return slot - 1;
+	}
+
+	/*
+	 * Parses an item count from 0 to 64 at the given index, or returns the
+	 * fallback if the argument is absent.
+	 */
+	private static int parseCount(Object[] arguments, int index, int fallback)
+			throws LuaException {
+		if (arguments.length <= index || arguments[index] == null) {
+			return fallback;
+		}
+
+		if (!(arguments[index] instanceof Double)) {
+			throw new LuaException("Expected number");
+		}
+
+		int count = (int) ((Double) arguments[index]).doubleValue();
+		if (count < 0 || count > STACK_SIZE) {
+			throw new LuaException("Quantity out of range");
+		}
+
+		return count;
+	}
+
+	/*
+	 * Yields until the turtle's response to a command arrives, like in-game,
+	 * then returns the command's result.
+	 */
+	private Object[] respond(ILuaContext context, Object[] result)
+			throws LuaException, InterruptedException {
+		this.commandID++;
+		int id = this.commandID;
+		this.computer.queueEvent("turtle_response", new Object[] {
+			new Integer(id),
+			result[0],
+		});
+
+		while (true) {
+			Object[] event = context.pullEvent("turtle_response");
+			if (event.length >= 2 && event[1] instanceof Number &&
+					((Number) event[1]).intValue() == id) {
+				return result;
+			}
+		}
+	}
+
+
+	/*
+	 * Commands
+	 */
+
+	private Object[] move(int forward, int vertical) {
+		if (this.needsFuel && this.getFuelLevel() <= 0) {
+			return failure("Out of fuel");
+		}
+
+		int[] position = this.getBlockPosition();
+		int[] destination;
+		if (vertical != 0) {
+			destination = this.target(vertical);
+		} else {
+			int[] offset = OFFSETS[this.direction];
+			destination = new int[] {
+				position[0] + offset[0] * forward,
+				position[1],
+				position[2] + offset[1] * forward,
+			};
+		}
+
+		String error = this.world.moveTurtle(this, destination[0], destination[1],
+			destination[2]);
+		if (error != null) {
+			return failure(error);
+		}
+
+		if (this.needsFuel) {
+			synchronized (this) {
+				this.fuel--;
+			}
+		}
+
+		return success();
+	}
+
+	private Object[] turn(int amount) {
+		this.direction = (this.direction + amount + DIRECTIONS.length) % DIRECTIONS.length;
+		return success();
+	}
+
+	private Object[] dig(int vertical) {
+		int[] block = this.target(vertical);
+		String name = this.world.getBlock(block[0], block[1], block[2]);
+		if (name == null) {
+			return failure("Nothing to dig here");
+		} else if (!World.isBreakable(name)) {
+			return failure("Unbreakable block detected");
+		}
+
+		this.world.setBlock(block[0], block[1], block[2], null);
+
+		// Like in-game, items that don't fit in the inventory are lost
+		this.store(World.drop(name), 1);
+		return success();
+	}
+
+	private Object[] place(int vertical) {
+		int[] block = this.target(vertical);
+		if (this.getItemCount(this.selected) == 0) {
+			return failure("No items to place");
+		} else if (this.world.isOccupied(block[0], block[1], block[2])) {
+			return failure("Cannot place block here");
+		}
+
+		String item = this.getItem(this.selected);
+		this.take(this.selected, 1);
+		this.world.setBlock(block[0], block[1], block[2], item);
+		return success();
+	}
+
+	private Object[] detect(int vertical) {
+		int[] block = this.target(vertical);
+		return new Object[] {new Boolean(this.world.isOccupied(block[0], block[1], block[2]))};
+	}
+
+	private Object[] inspect(int vertical) {
+		int[] block = this.target(vertical);
+		String name = this.world.getBlock(block[0], block[1], block[2]);
+		if (name == null) {
+			return failure("No block to inspect");
+		}
+
+		Map<Object, Object> table = new HashMap<Object, Object>();
+		table.put("name", name);
+		table.put("metadata", new Integer(0));
+		return new Object[] {Boolean.TRUE, table};
+	}
+
+	private Object[] compare(int vertical) {
+		int[] block = this.target(vertical);
+		String name = this.world.getBlock(block[0], block[1], block[2]);
+		String item = this.getItem(this.selected);
+		// Like in-game, the block is compared with the selected item's block,
+		// not with what the block would drop
+		boolean same = name == null ? item == null : name.equals(item);
+		return new Object[] {new Boolean(same)};
+	}
+
+	private Object[] getItemDetail(int slot) {
+		String item = this.getItem(slot);
+		if (item == null) {
+			return new Object[] {null};
+		}
+
+		Map<Object, Object> table = new HashMap<Object, Object>();
+		table.put("name", item);
+		table.put("count", new Integer(this.getItemCount(slot)));
+		table.put("damage", new Integer(0));
+		return new Object[] {table};
+	}
+
+	private synchronized Object[] transferTo(int slot, int count) {
+		if (slot == this.selected || this.items[this.selected] == null) {
+			return success();
+		}
+
+		String item = this.items[this.selected];
+		if (this.items[slot] != null && !this.items[slot].equals(item)) {
+			return failure("No space for items");
+		}
+
+		int moved = Math.min(Math.min(count, this.counts[this.selected]),
+			STACK_SIZE - this.counts[slot]);
+		if (moved <= 0) {
+			return failure("No space for items");
+		}
+
+		this.take(this.selected, moved);
+		this.items[slot] = item;
+		this.counts[slot] += moved;
+		return success();
+	}
+
+	private Object[] drop(int count) {
+		if (this.getItemCount(this.selected) == 0) {
+			return failure("No items to drop");
+		}
+
+		// There are no inventories in the world, so dropped items are lost
+		this.take(this.selected, count);
+		return success();
+	}
+
+	/*
+	 * Attacks in a direction. There are no mobs in the world, so there's
+	 * never anything to hit.
+	 */
+	private Object[] attack() {
+		return failure("Nothing to attack here");
+	}
+
+	/*
+	 * Picks up items from a direction. There are no inventories or dropped
+	 * items in the world, so there's never anything to take.
+	 */
+	private Object[] suck() {
+		return failure("No items to take");
+	}
+
+	/*
+	 * Equips the selected item as an upgrade. There are no turtle upgrades,
+	 * so only an empty slot, swapping nothing, succeeds.
+	 */
+	private Object[] equip() {
+		if (this.getItemCount(this.selected) > 0) {
+			return failure("Not a valid upgrade");
+		}
+
+		return success();
+	}
+
+	private synchronized Object[] refuel(int count) {
+		String item = this.items[this.selected];
+		int value = fuelValue(item);
+		if (item == null || value == 0) {
+			return failure("Items not combustible");
+		}
+
+		int burnt = Math.min(count, this.counts[this.selected]);
+		if (!this.needsFuel) {
+			return success();
+		}
+
+		burnt = Math.min(burnt, (this.fuelLimit - this.fuel + value - 1) / value);
+		this.take(this.selected, burnt);
+		this.fuel = Math.min(this.fuelLimit, this.fuel + burnt * value);
+		return success();
+	}
+
+
+	/*
+	 * Lua API
+	 */
+
+	@Override
+	public String[] getNames() {
+		return new String[] {"turtle"};
+	}
+
+	@Override
+	public void startup() {
+	}
+
+	@Override
+	public void advance(double delta) {
+	}
+
+	@Override
+	public void shutdown() {
+	}
+
+	@Override
+	public String[] getMethodNames() {
+		return METHODS;
+	}
+
+	@Override
+	public Object[] callMethod(ILuaContext context, int method, Object[] arguments)
+			throws LuaException, InterruptedException {
+		switch (method) {
+		case 0: return this.respond(context, this.move(1, 0));
+		case 1: return this.respond(context, this.move(-1, 0));
+		case 2: return this.respond(context, this.move(0, 1));
+		case 3: return this.respond(context, this.move(0, -1));
+		case 4: return this.respond(context, this.turn(-1));
+		case 5: return this.respond(context, this.turn(1));
+		case 6: return this.respond(context, this.dig(0));
+		case 7: return this.respond(context, this.dig(1));
+		case 8: return this.respond(context, this.dig(-1));
+		case 9: return this.respond(context, this.place(0));
+		case 10: return this.respond(context, this.place(1));
+		case 11: return this.respond(context, this.place(-1));
+		case 12: return this.respond(context, this.detect(0));
+		case 13: return this.respond(context, this.detect(1));
+		case 14: return this.respond(context, this.detect(-1));
+		case 15: return this.respond(context, this.inspect(0));
+		case 16: return this.respond(context, this.inspect(1));
+		case 17: return this.respond(context, this.inspect(-1));
+		case 18: return this.respond(context, this.compare(0));
+		case 19: return this.respond(context, this.compare(1));
+		case 20: return this.respond(context, this.compare(-1));
+		case 21:
+			// Select
+			int slot = parseSlot(arguments, 0, -1);
+			if (slot < 0) {
+				throw new LuaException("Expected number");
+			}
+
+			synchronized (this) {
+				this.selected = slot;
+			}
+
+			return this.respond(context, success());
+		case 22: return new Object[] {new Integer(this.getSelectedSlot() + 1)};
+		case 23:
+			return new Object[] {
+				new Integer(this.getItemCount(parseSlot(arguments, 0, this.getSelectedSlot()))),
+			};
+		case 24:
+			return new Object[] {
+				new Integer(STACK_SIZE -
+					this.getItemCount(parseSlot(arguments, 0, this.getSelectedSlot()))),
+			};
+		case 25: return this.getItemDetail(parseSlot(arguments, 0, this.getSelectedSlot()));
+		case 26:
+			// Transfer to
+			int destination = parseSlot(arguments, 0, -1);
+			if (destination < 0) {
+				throw new LuaException("Expected number");
+			}
+
+			return this.respond(context, this.transferTo(destination,
+				parseCount(arguments, 1, STACK_SIZE)));
+		case 27:
+		case 28:
+		case 29:
+			return this.respond(context, this.drop(parseCount(arguments, 0, STACK_SIZE)));
+		case 30:
+			if (!this.needsFuel) {
+				return new Object[] {"unlimited"};
+			}
+
+			return new Object[] {new Integer(this.getFuelLevel())};
+		case 31:
+			if (!this.needsFuel) {
+				return new Object[] {"unlimited"};
+			}
+
+			return new Object[] {new Integer(this.fuelLimit)};
+		case 32: return this.respond(context, this.refuel(parseCount(arguments, 0, STACK_SIZE)));
+		case 33:
+		case 34:
+		case 35:
+			return this.respond(context, this.attack());
+		case 36:
+		case 37:
+		case 38:
+			parseCount(arguments, 0, STACK_SIZE);
+			return this.respond(context, this.suck());
+		case 39:
+		case 40:
+			return this.respond(context, this.equip());
+		}
+
+		return new Object[] {};
+	}
+
+}
@@ -0,0 +1,247 @@
+/*
+ * World
+ *
+ * A simple voxel world for turtles to move around in. Each block is just a
+ * name, such as `minecraft:stone`, and anything not set is air.
+ */
+
+
+import java.io.BufferedReader;
+import java.io.File;
+import java.io.FileReader;
+import java.io.IOException;
+import java.util.Map;
+import java.util.HashMap;
+import java.util.List;
+import java.util.ArrayList;
+
+
+public class World {
+
+	/*
+	 * The lowest and highest heights blocks and turtles can be at.
+	 */
+	public static final int MIN_HEIGHT = 0;
+	public static final int MAX_HEIGHT = 255;
+
+	/*
+	 * Blocks that can't be dug.
+	 */
+	private static final String[] UNBREAKABLE = new String[] {
+		"minecraft:bedrock",
+	};
+
+	/*
+	 * Blocks that drop something other than themselves when dug, as pairs of
+	 * block and dropped item.
+	 */
+	private static final String[][] DROPS = new String[][] {
+		{"minecraft:stone", "minecraft:cobblestone"},
+		{"minecraft:grass", "minecraft:dirt"},
+		{"minecraft:coal_ore", "minecraft:coal"},
+		{"minecraft:diamond_ore", "minecraft:diamond"},
+		{"minecraft:redstone_ore", "minecraft:redstone"},
+	};
+
+	/*
+	 * How blocks are drawn in the world view, as the block name followed by a
+	 * character and its text and background colors, as hex digits like in
+	 * terminal color lines.
+	 */
+	private static final String[][] SYMBOLS = new String[][] {
+		{"minecraft:stone", "#87"},
+		{"minecraft:cobblestone", "#87"},
+		{"minecraft:dirt", ":cf"},
+		{"minecraft:grass", "\"df"},
+		{"minecraft:sand", ".4f"},
+		{"minecraft:gravel", ":87"},
+		{"minecraft:water", "~3b"},
+		{"minecraft:lava", "~4e"},
+		{"minecraft:log", "Ocf"},
+		{"minecraft:planks", "=cf"},
+		{"minecraft:bedrock", "=8f"},
+		{"minecraft:coal_ore", "*f8"},
+		{"minecraft:coal", "*8f"},
+		{"minecraft:diamond_ore", "*38"},
+		{"minecraft:diamond", "*3f"},
+		{"minecraft:redstone_ore", "*e8"},
+		{"minecraft:redstone", "*ef"},
+	};
+
+	private static World world = new World();
+
+	private Map<String, String> blocks;
+	private List<Turtle> turtles;
+
+	private World() {
+		this.blocks = new HashMap<String, String>();
+		this.turtles = new ArrayList<Turtle>();
+	}
+
+	/*
+	 * Returns the world shared by every turtle.
+	 */
+	public static World get() {
+		return world;
+	}
+
+	private static String key(int x, int y, int z) {
+		return x + "," + y + "," + z;
+	}
+
+	/*
+	 * Replaces every block in the world with those listed in a file. Each line
+	 * is a block's x, y and z coordinates and name, separated by spaces.
+	 * Blank lines and lines starting with # are ignored.
+	 */
+	public synchronized void load(String path) throws IOException {
+		Map<String, String> loaded = new HashMap<String, String>();
+		BufferedReader reader = new BufferedReader(new FileReader(new File(path)));
+
+		try {
+			String line;
+			int number = 0;
+			while ((line = reader.readLine()) != null) {
+				number++;
+				line = line.trim();
+				if (line.length() == 0 || line.startsWith("#")) {
+					continue;
+				}
+
+				String[] fields = line.split("\\s+");
+				if (fields.length != 4) {
+					throw new IOException("Expected `x y z name` on line " + number);
+				}
+
+				try {
+					int x = Integer.parseInt(fields[0]);
+					int y = Integer.parseInt(fields[1]);
+					int z = Integer.parseInt(fields[2]);
+					loaded.put(key(x, y, z), fields[3]);
+				} catch (NumberFormatException e) {
+					throw new IOException("Invalid coordinate on line " + number);
+				}
+			}
+		} finally {
+			reader.close();
+		}
+
+		this.blocks = loaded;
+	}
+
+	/*
+	 * Returns the name of the block at a position, or null for air.
+	 */
+	public synchronized String getBlock(int x, int y, int z) {
+		return this.blocks.get(key(x, y, z));
+	}
+
+	/*
+	 * Sets the block at a position, or removes it if the name is null.
+	 */
+	public synchronized void setBlock(int x, int y, int z, String name) {
+		if (name == null) {
+			this.blocks.remove(key(x, y, z));
+		} else {
+			this.blocks.put(key(x, y, z), name);
+		}
+	}
+
+	/*
+	 * Returns the turtle at a position, or null.
+	 */
+	public synchronized Turtle getTurtle(int x, int y, int z) {
+		for (Turtle turtle : this.turtles) {
+			int[] position = turtle.getBlockPosition();
+			if (position[0] == x && position[1] == y && position[2] == z) {
+				return turtle;
+			}
+		}
+
+		return null;
+	}
+
+	/*
+	 * Returns true if there's a block or turtle at a position.
+	 */
+	public synchronized boolean isOccupied(int x, int y, int z) {
+		return this.getBlock(x, y, z) != null || this.getTurtle(x, y, z) != null;
+	}
+
+	/*
+	 * Adds a turtle to the world, so other turtles can't move through it.
+	 */
+	public synchronized void addTurtle(Turtle turtle) {
+		if (!this.turtles.contains(turtle)) {
+			this.turtles.add(turtle);
+		}
+	}
+
+	/*
+	 * Removes a turtle from the world.
+	 */
+	public synchronized void removeTurtle(Turtle turtle) {
+		this.turtles.remove(turtle);
+	}
+
+	/*
+	 * Moves a turtle to a position if nothing is in the way, returning an
+	 * error message, or null if it moved.
+	 */
+	public synchronized String moveTurtle(Turtle turtle, int x, int y, int z) {
+		if (y < MIN_HEIGHT) {
+			return "Too low to move";
+		} else if (y > MAX_HEIGHT) {
+			return "Too high to move";
+		} else if (this.isOccupied(x, y, z)) {
+			return "Movement obstructed";
+		}
+
+		turtle.setBlockPosition(x, y, z);
+		return null;
+	}
+
+	/*
+	 * Returns true if the block with the given name can be dug.
+	 */
+	public static boolean isBreakable(String name) {
+		for (String unbreakable : UNBREAKABLE) {
+			if (unbreakable.equals(name)) {
+				return false;
+			}
+		}
+
+		return true;
+	}
+
+	/*
+	 * Returns how a block or item is drawn in the world view, as a character
+	 * followed by its text and background colors as hex digits. Unknown
+	 * blocks are drawn as the first letter of their name.
+	 */
+	public static String symbol(String name) {
+		for (String[] pair : SYMBOLS) {
+			if (pair[0].equals(name)) {
+				return pair[1];
+			}
+		}
+
+		String shortName = name.substring(name.indexOf(':') + 1);
+		char letter = shortName.length() > 0 ? shortName.charAt(0) : '?';
+		return letter + "07";
+	}
+
+	/*
+	 * Returns the item dropped when the block with the given name is dug.
+	 */
+	public static String drop(String name) {
+		for (String[] pair : DROPS) {
+			if (pair[0].equals(name)) {
+				return pair[1];
+			}
+		}
+
+		return name;
+	}
+
+}
@@ -0,0 +1,151 @@
+/*
+ * Archive Tests
+ */
+
+
+import java.io.File;
+import java.io.FileOutputStream;
+import java.io.IOException;
+import java.util.Properties;
+import java.util.zip.ZipEntry;
+import java.util.zip.ZipOutputStream;
+
+
+public class ArchiveTest {
+
+	/*
+	 * Writes an archive holding metadata and the given files, as pairs of
+	 * entry name and contents.
+	 */
+	private static File archive(String... entries) throws IOException {
+		File archive = new File(Tests.directory(), "computer.zip");
+		ZipOutputStream zip = new ZipOutputStream(new FileOutputStream(archive));
+		try {
+			zip.putNextEntry(new ZipEntry(Archive.METADATA));
+			new Properties().store(zip, null);
+			zip.closeEntry();
+
+			for (int i = 0; i < entries.length; i += 2) {
+				zip.putNextEntry(new ZipEntry(entries[i]));
+				zip.write(entries[i + 1].getBytes());
+				zip.closeEntry();
+			}
+		} finally {
+			zip.close();
+		}
+
+		return archive;
+	}
+
+	/*
+	 * Returns a computer's folder holding a single file.
+	 */
+	private static File computer() throws IOException {
+		return computer(Tests.directory());
+	}
+
+	private static File computer(File parent) throws IOException {
+		File directory = new File(parent, "0");
+		Tests.write(new File(directory, "existing"), "kept");
+		return directory;
+	}
+
+	public static void testExportAndImport() throws Exception {
+		File original = computer();
+		Tests.write(new File(original, "folder/nested"), "nested");
+
+		Properties metadata = new Properties();
+		metadata.setProperty("label", "test");
+		File archive = new File(Tests.directory(), "export.zip");
+		Archive.export(original, archive, metadata);
+		Tests.checkEqual("test", Archive.readMetadata(archive).getProperty("label"));
+
+		File copy = new File(Tests.directory(), "1");
+		Archive.extract(archive, copy, Archive.FAIL);
+		Tests.checkEqual("kept\n", Tests.read(new File(copy, "existing")));
+		Tests.checkEqual("nested\n", Tests.read(new File(copy, "folder/nested")));
+	}
+
+	public static void testReplace() throws Exception {
+		File directory = computer();
+		Archive.extract(archive("files/new", "new"), directory, Archive.REPLACE);
+
+		Tests.check(!new File(directory, "existing").exists(), "old file kept");
+		Tests.checkEqual("new\n", Tests.read(new File(directory, "new")));
+		Tests.check(!new File(directory.getPath() + ".import").exists(), "staging left");
+		Tests.check(!new File(directory.getPath() + ".old").exists(), "old files left");
+	}
+
+	public static void testReplaceInsideLink() throws Exception {
+		for (String mode : new String[] {Archive.REPLACE, Archive.MERGE}) {
+			File directory = computer(Tests.linkedDirectory());
+			Tests.write(new File(directory, "folder/nested"), "nested");
+			Archive.extract(archive("files/new", "new"), directory, mode);
+
+			Tests.checkEqual("new\n", Tests.read(new File(directory, "new")));
+			Tests.checkEqual(mode.equals(Archive.MERGE),
+				new File(directory, "folder/nested").exists());
+			Tests.check(!new File(directory.getPath() + ".old").exists(), "old files left");
+		}
+	}
+
+	public static void testMerge() throws Exception {
+		File directory = computer();
+		Archive.extract(archive("files/new", "new"), directory, Archive.MERGE);
+
+		Tests.checkEqual("kept\n", Tests.read(new File(directory, "existing")));
+		Tests.checkEqual("new\n", Tests.read(new File(directory, "new")));
+	}
+
+	public static void testFailWithExistingFiles() throws Exception {
+		File directory = computer();
+		try {
+			Archive.extract(archive("files/new", "new"), directory, Archive.FAIL);
+			Tests.check(false, "imported over existing files");
+		} catch (IOException e) {
+			// Expected
+		}
+
+		Tests.check(!new File(directory, "new").exists(), "extracted anyway");
+	}
+
+	public static void testRejectsEscapingEntries() throws Exception {
+		File directory = computer();
+		File archive = archive("files/new", "new", "files/../../escaped", "escaped");
+
+		for (String mode : new String[] {Archive.REPLACE, Archive.MERGE}) {
+			try {
+				Archive.extract(archive, directory, mode);
+				Tests.check(false, "extracted an entry outside the computer's files");
+			} catch (IOException e) {
+				Tests.check(e.getMessage().indexOf("outside") != -1, e.getMessage());
+			}
+
+			// Nothing was deleted or written
+			Tests.checkEqual("kept\n", Tests.read(new File(directory, "existing")));
+			Tests.check(!new File(directory, "new").exists(), "extracted some entries");
+			Tests.check(!new File(directory.getParentFile().getParentFile(), "escaped").exists(),
+				"escaped the computer's files");
+			Tests.check(!new File(directory.getPath() + ".import").exists(), "staging left");
+		}
+	}
+
+	public static void testRejectsOtherArchives() throws Exception {
+		File directory = computer();
+		File archive = new File(Tests.directory(), "other.zip");
+		ZipOutputStream zip = new ZipOutputStream(new FileOutputStream(archive));
+		zip.putNextEntry(new ZipEntry("files/new"));
+		zip.closeEntry();
+		zip.close();
+
+		try {
+			Archive.extract(archive, directory, Archive.REPLACE);
+			Tests.check(false, "imported an archive without metadata");
+		} catch (IOException e) {
+			Tests.checkEqual("Not a Mimic computer archive", e.getMessage());
+		}
+
+		Tests.checkEqual("kept\n", Tests.read(new File(directory, "existing")));
+	}
+
+}
@@ -0,0 +1,74 @@
+/*
+ * Faults Tests
+ */
+
+
+import java.util.List;
+import java.util.ArrayList;
+
+
+public class FaultsTest {
+
+	private static List<Integer> delays(Faults faults, Receiver receiver, int count) {
+		List<Integer> delays = new ArrayList<Integer>();
+		for (int i = 0; i < count; i++) {
+			delays.add(new Integer(faults.nextDelay(receiver)));
+		}
+
+		return delays;
+	}
+
+	public static void testSameSeedSameFaults() {
+		Receiver receiver = new NetworkTest.Listener(0, 0, 0, 64);
+		Faults first = new Faults(1, 0.0, 0.5, 1234);
+		Faults second = new Faults(1, 0.0, 0.5, 1234);
+
+		Tests.checkEqual(delays(first, receiver, 50), delays(second, receiver, 50));
+	}
+
+	public static void testReceiversHaveTheirOwnSequence() {
+		Receiver a = new NetworkTest.Listener(1, 0, 0, 64);
+		Receiver b = new NetworkTest.Listener(2, 0, 0, 64);
+
+		// Messages to another receiver in between don't change a receiver's
+		// faults, whatever order computers' threads run in
+		Faults alone = new Faults(1, 0.0, 0.5, 1234);
+		List<Integer> expected = delays(alone, a, 20);
+
+		Faults interleaved = new Faults(1, 0.0, 0.5, 1234);
+		List<Integer> actual = new ArrayList<Integer>();
+		for (int i = 0; i < 20; i++) {
+			delays(interleaved, b, i % 3);
+			actual.addAll(delays(interleaved, a, 1));
+		}
+
+		Tests.checkEqual(expected, actual);
+	}
+
+	public static void testDelayedInTicks() {
+		Network network = NetworkTest.network();
+		network.setFaults(new Faults(2, 0.0, 0.0, 1));
+		NetworkTest.Listener sender = new NetworkTest.Listener(0, 0, 0, 64);
+		NetworkTest.Listener receiver = new NetworkTest.Listener(0, 0, 0, 64);
+		network.listen(receiver, 1);
+
+		Network.advance(0.0);
+		network.transmit(sender, "hello", 1, 1);
+		Tests.checkEqual(0, receiver.messages.size());
+
+		// However long a frame takes, nothing arrives until two ticks pass
+		Network.advance(0.05);
+		Tests.checkEqual(0, receiver.messages.size());
+		Network.advance(0.05);
+		Tests.checkEqual(1, receiver.messages.size());
+	}
+
+	public static void testDrop() {
+		Receiver receiver = new NetworkTest.Listener(0, 0, 0, 64);
+		Faults always = new Faults(0, 1.0, 0.0, 1);
+		Faults never = new Faults(0, 0.0, 0.0, 1);
+		Tests.check(always.shouldDrop(receiver), "message not dropped");
+		Tests.check(!never.shouldDrop(receiver), "message dropped");
+	}
+
+}
@@ -0,0 +1,63 @@
+/*
+ * Minion Tests
+ */
+
+
+import java.io.File;
+
+import dan200.computercraft.api.peripheral.IPeripheral;
+
+
+public class MinionTest {
+
+	private static Minion pocket(File directory) {
+		Minion minion = new Minion(0, false, 26, 20, directory.getPath(), null, 0, false);
+		minion.makePocket("speaker", "");
+		return minion;
+	}
+
+	private static void play(IPeripheral speaker) throws Exception {
+		Object[] note = new Object[] {"harp", new Double(1.0), new Double(12.0)};
+		speaker.callMethod(null, null, 0, note);
+	}
+
+	public static void testSpeakersRecordPerSide() throws Exception {
+		File directory = Tests.directory();
+		Minion minion = pocket(directory);
+		minion.attachSpeaker("left");
+		Tests.check(minion.hasSpeaker("back"), "pocket speaker missing");
+		Tests.check(minion.hasSpeaker("left"), "left speaker missing");
+
+		play(minion.getPeripheral("back"));
+		minion.advance(0.5);
+		play(minion.getPeripheral("left"));
+		play(minion.getPeripheral("left"));
+		minion.destroy();
+
+		File audio = new File(directory, "audio");
+		String back = Tests.read(new File(audio, "computer_0_back.txt"));
+		String left = Tests.read(new File(audio, "computer_0_left.txt"));
+		Tests.checkEqual(1, back.split("\n").length);
+		Tests.checkEqual(2, left.split("\n").length);
+		Tests.check(new File(audio, "computer_0_back.wav").exists(), "pocket audio not saved");
+		Tests.check(new File(audio, "computer_0_left.wav").exists(), "left audio not saved");
+	}
+
+	public static void testDetachPocketSpeaker() throws Exception {
+		File directory = Tests.directory();
+		Minion minion = pocket(directory);
+		minion.attachSpeaker("left");
+		play(minion.getPeripheral("back"));
+
+		minion.detachSpeaker("back");
+		Tests.check(!minion.hasSpeaker("back"), "pocket speaker not detached");
+		Tests.check(minion.hasSpeaker("left"), "left speaker detached with the pocket one");
+		Tests.check(new File(directory, "audio/computer_0_back.wav").exists(),
+			"pocket audio not saved on detach");
+
+		minion.attachSpeaker("back");
+		Tests.check(minion.hasSpeaker("back"), "pocket speaker not attached again");
+		minion.destroy();
+	}
+
+}
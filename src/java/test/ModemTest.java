@@ -0,0 +1,170 @@
+/*
+ * Modem Tests
+ */
+
+
+import java.util.List;
+import java.util.ArrayList;
+import java.util.Collections;
+
+import dan200.computercraft.api.filesystem.IMount;
+import dan200.computercraft.api.filesystem.IWritableMount;
+import dan200.computercraft.api.peripheral.IComputerAccess;
+
+
+public class ModemTest {
+
+	/*
+	 * A computer a peripheral can be attached to, recording the events queued
+	 * on it.
+	 */
+	public static class Computer implements IComputerAccess {
+
+		public List<String> events = Collections.synchronizedList(new ArrayList<String>());
+
+		public String mount(String desiredLocation, IMount mount) {
+			return desiredLocation;
+		}
+
+		public String mountWritable(String desiredLocation, IWritableMount mount) {
+			return desiredLocation;
+		}
+
+		public void unmount(String location) {}
+
+		public int getID() {
+			return 0;
+		}
+
+		public void queueEvent(String event, Object[] arguments) {
+			this.events.add(event);
+		}
+
+		public String getAttachmentName() {
+			return "top";
+		}
+
+	}
+
+	private static Modem modem(Network network, Computer computer) {
+		Modem modem = new Modem(null, network, null, false);
+		modem.attach(computer);
+		return modem;
+	}
+
+	public static void testCloseAll() throws Exception {
+		Network network = NetworkTest.network();
+		Computer computer = new Computer();
+		Modem modem = modem(network, computer);
+		modem.open(1);
+		modem.open(2);
+
+		modem.closeAll();
+		Tests.check(!modem.isOpen(1) && !modem.isOpen(2), "channels still open");
+
+		modem(network, new Computer()).transmit("hello", 1, 1);
+		Tests.checkEqual(0, computer.events.size());
+	}
+
+	public static void testDetachClosesChannels() throws Exception {
+		Network network = NetworkTest.network();
+		Computer computer = new Computer();
+		Modem modem = modem(network, computer);
+		modem.open(1);
+
+		modem.detach(computer);
+		Tests.check(!modem.isOpen(1), "channel still open after detach");
+
+		// Attaching again, such as after a reboot, starts with no channels
+		modem.attach(computer);
+		modem(network, new Computer()).transmit("hello", 1, 1);
+		Tests.checkEqual(0, computer.events.size());
+	}
+
+	public static void testReceivesOnOpenChannels() throws Exception {
+		Network network = NetworkTest.network();
+		Computer computer = new Computer();
+		Modem modem = modem(network, computer);
+		modem.open(1);
+
+		Modem sender = modem(network, new Computer());
+		sender.transmit("hello", 1, 1);
+		sender.transmit("hello", 2, 2);
+		Tests.checkEqual(1, computer.events.size());
+		Tests.checkEqual("modem_message", computer.events.get(0));
+	}
+
+	public static void testConcurrentListening() throws Exception {
+		final Network network = NetworkTest.network();
+		final Computer[] computers = new Computer[8];
+		final Modem[] modems = new Modem[computers.length];
+		for (int i = 0; i < computers.length; i++) {
+			computers[i] = new Computer();
+			modems[i] = modem(network, computers[i]);
+		}
+
+		// Each computer opens and closes channels on its own thread, leaving
+		// only channel 1 open, while another transmits on every channel
+		final List<Throwable> errors = Collections.synchronizedList(new ArrayList<Throwable>());
+		List<Thread> threads = new ArrayList<Thread>();
+		for (int i = 0; i < modems.length; i++) {
+			final Modem modem = modems[i];
+			threads.add(new Thread(new Runnable() {
+				public void run() {
+					try {
+						for (int round = 0; round < 200; round++) {
+							for (int channel = 1; channel <= 16; channel++) {
+								modem.open(channel);
+							}
+
+							for (int channel = 2; channel <= 16; channel++) {
+								modem.close(channel);
+							}
+						}
+					} catch (Throwable e) {
+						errors.add(e);
+					}
+				}
+			}));
+		}
+
+		final Modem sender = modem(network, new Computer());
+		threads.add(new Thread(new Runnable() {
+			public void run() {
+				try {
+					for (int round = 0; round < 200; round++) {
+						for (int channel = 1; channel <= 16; channel++) {
+							sender.transmit("noise", channel, channel);
+						}
+					}
+				} catch (Throwable e) {
+					errors.add(e);
+				}
+			}
+		}));
+
+		for (Thread thread : threads) {
+			thread.start();
+		}
+
+		for (Thread thread : threads) {
+			thread.join();
+		}
+
+		Tests.checkEqual(0, errors.size());
+
+		for (int i = 0; i < modems.length; i++) {
+			Tests.check(modems[i].isOpen(1), "channel 1 closed");
+			Tests.check(!modems[i].isOpen(2), "channel 2 left open");
+			computers[i].events.clear();
+		}
+
+		// The network's listeners agree with the modems' open channels
+		sender.transmit("hello", 1, 1);
+		sender.transmit("hello", 2, 2);
+		for (Computer computer : computers) {
+			Tests.checkEqual(1, computer.events.size());
+		}
+	}
+
+}
@@ -0,0 +1,106 @@
+/*
+ * Network Tests
+ */
+
+
+import java.util.List;
+import java.util.ArrayList;
+
+
+public class NetworkTest {
+
+	/*
+	 * A receiver at a fixed position, recording the messages it hears.
+	 */
+	public static class Listener implements Receiver {
+
+		public double[] position;
+		public double range;
+		public List<Object> messages;
+
+		public Listener(double x, double y, double z, double range) {
+			this.position = new double[] {x, y, z};
+			this.range = range;
+			this.messages = new ArrayList<Object>();
+		}
+
+		public double[] getPosition() {
+			return this.position;
+		}
+
+		public double getRange() {
+			return this.range;
+		}
+
+		public String getName() {
+			return "listener " + this.position[0];
+		}
+
+		public synchronized void receive(Object message, int channel, int replyChannel,
+				double distance) {
+			this.messages.add(message);
+		}
+
+	}
+
+	private static int count = 0;
+
+	/*
+	 * Returns a network no other test uses.
+	 */
+	public static Network network() {
+		count++;
+		return Network.get("test " + count);
+	}
+
+	public static void testOutOfRange() {
+		Network network = network();
+		Listener sender = new Listener(0, 0, 0, 64);
+		Listener receiver = new Listener(100, 0, 0, 64);
+		network.listen(receiver, 1);
+
+		network.transmit(sender, "hello", 1, 1);
+		Tests.checkEqual(0, receiver.messages.size());
+	}
+
+	public static void testSenderRange() {
+		Network network = network();
+		Listener sender = new Listener(0, 0, 0, 128);
+		Listener receiver = new Listener(100, 0, 0, 64);
+		network.listen(receiver, 1);
+
+		network.transmit(sender, "hello", 1, 1);
+		Tests.checkEqual(1, receiver.messages.size());
+	}
+
+	public static void testReceiverRange() {
+		Network network = network();
+		Listener sender = new Listener(0, 0, 0, 64);
+		Listener receiver = new Listener(100, 0, 0, 128);
+		network.listen(receiver, 1);
+
+		network.transmit(sender, "hello", 1, 1);
+		Tests.checkEqual(1, receiver.messages.size());
+	}
+
+	public static void testEnderRange() {
+		Network network = network();
+		Listener sender = new Listener(0, 0, 0, -1);
+		Listener receiver = new Listener(100000, 0, 0, 64);
+		network.listen(receiver, 1);
+
+		network.transmit(sender, "hello", 1, 1);
+		Tests.checkEqual(1, receiver.messages.size());
+	}
+
+	public static void testOtherChannels() {
+		Network network = network();
+		Listener sender = new Listener(0, 0, 0, 64);
+		Listener receiver = new Listener(0, 0, 0, 64);
+		network.listen(receiver, 2);
+
+		network.transmit(sender, "hello", 1, 1);
+		Tests.checkEqual(0, receiver.messages.size());
+	}
+
+}
@@ -0,0 +1,181 @@
+/*
+ * Save Mount Tests
+ */
+
+
+import java.io.ByteArrayInputStream;
+import java.io.ByteArrayOutputStream;
+import java.io.IOException;
+import java.io.InputStream;
+import java.io.OutputStream;
+import java.util.List;
+import java.util.ArrayList;
+import java.util.Map;
+import java.util.HashMap;
+
+import dan200.computercraft.api.filesystem.IWritableMount;
+
+
+public class SaveMountTest {
+
+	/*
+	 * A mount holding its files in memory. Folders exist whenever a file is
+	 * inside them.
+	 */
+	public static class Memory implements IWritableMount {
+
+		public Map<String, String> files = new HashMap<String, String>();
+
+		public Memory(String... paths) {
+			for (String path : paths) {
+				this.files.put(path, path);
+			}
+		}
+
+		public boolean exists(String path) {
+			return this.files.containsKey(path) || this.isDirectory(path);
+		}
+
+		public boolean isDirectory(String path) {
+			if (path.length() == 0) {
+				return true;
+			}
+
+			for (String file : this.files.keySet()) {
+				if (file.startsWith(path + "/")) {
+					return true;
+				}
+			}
+
+			return false;
+		}
+
+		public void list(String path, List<String> contents) {
+			String prefix = path.length() == 0 ? "" : path + "/";
+			for (String file : this.files.keySet()) {
+				if (file.startsWith(prefix)) {
+					String name = file.substring(prefix.length()).split("/")[0];
+					if (!contents.contains(name)) {
+						contents.add(name);
+					}
+				}
+			}
+		}
+
+		public long getSize(String path) {
+			return this.files.containsKey(path) ? this.files.get(path).length() : 0;
+		}
+
+		public InputStream openForRead(String path) throws IOException {
+			if (!this.files.containsKey(path)) {
+				throw new IOException("No such file");
+			}
+
+			return new ByteArrayInputStream(this.files.get(path).getBytes());
+		}
+
+		public void makeDirectory(String path) {}
+
+		public void delete(String path) {
+			this.files.remove(path);
+		}
+
+		public OutputStream openForWrite(final String path) {
+			return new ByteArrayOutputStream() {
+				public void close() {
+					files.put(path, this.toString());
+				}
+			};
+		}
+
+		public OutputStream openForAppend(String path) {
+			return this.openForWrite(path);
+		}
+
+		public long getRemainingSpace() {
+			return 1000000;
+		}
+
+	}
+
+	private static String read(SaveMount mount, String path) throws IOException {
+		InputStream stream = mount.openForRead(path);
+		StringBuilder contents = new StringBuilder();
+		int b;
+		while ((b = stream.read()) != -1) {
+			contents.append((char) b);
+		}
+
+		stream.close();
+		return contents.toString();
+	}
+
+	public static void testInnermostOverlayWins() throws Exception {
+		// Both orders, so the result doesn't depend on which was added first
+		for (int order = 0; order < 2; order++) {
+			SaveMount mount = new SaveMount(new Memory("save"));
+			Memory outer = new Memory("outer", "extra/hidden");
+			Memory inner = new Memory("inner");
+			if (order == 0) {
+				mount.addMount("lib", outer, true);
+				mount.addMount("lib/extra", inner, false);
+			} else {
+				mount.addMount("lib/extra", inner, false);
+				mount.addMount("lib", outer, true);
+			}
+
+			Tests.checkEqual("outer", read(mount, "lib/outer"));
+			Tests.checkEqual("inner", read(mount, "lib/extra/inner"));
+			Tests.check(!mount.exists("lib/extra/hidden"), "outer mount shows through");
+
+			// Writable inside the inner mount, even though the outer is read only
+			OutputStream stream = mount.openForWrite("lib/extra/new");
+			stream.close();
+			Tests.check(inner.files.containsKey("new"), "file not written to inner mount");
+		}
+	}
+
+	public static void testListsNestedOverlays() throws Exception {
+		SaveMount mount = new SaveMount(new Memory("save"));
+		mount.addMount("lib", new Memory("outer"), true);
+		mount.addMount("lib/extra", new Memory("inner"), false);
+
+		List<String> contents = new ArrayList<String>();
+		mount.list("lib", contents);
+		Tests.check(contents.contains("outer"), "missing outer file");
+		Tests.check(contents.contains("extra"), "missing nested overlay");
+
+		contents.clear();
+		mount.list("", contents);
+		Tests.check(contents.contains("save"), "missing saved file");
+		Tests.check(contents.contains("lib"), "missing overlay");
+	}
+
+	public static void testOverlaysCantBeReplaced() throws Exception {
+		SaveMount mount = new SaveMount(new Memory("save"));
+		mount.addMount("lib", new Memory("outer"), false);
+		mount.addMount("lib/extra", new Memory("inner"), false);
+
+		for (String path : new String[] {"lib", "lib/extra"}) {
+			try {
+				mount.delete(path);
+				Tests.check(false, "deleted overlay " + path);
+			} catch (IOException e) {
+				Tests.checkEqual("Access denied", e.getMessage());
+			}
+		}
+	}
+
+	public static void testReadOnlyOverlay() throws Exception {
+		SaveMount mount = new SaveMount(new Memory("save"));
+		mount.addMount("lib", new Memory("outer"), true);
+
+		try {
+			mount.openForWrite("lib/outer");
+			Tests.check(false, "wrote to a read only overlay");
+		} catch (IOException e) {
+			Tests.checkEqual("Access denied", e.getMessage());
+		}
+	}
+
+}
@@ -0,0 +1,123 @@
+/*
+ * Serializer Tests
+ */
+
+
+import java.util.Map;
+import java.util.HashMap;
+
+
+public class SerializerTest {
+
+	private static String nested(int depth) {
+		StringBuilder builder = new StringBuilder();
+		for (int i = 0; i < depth; i++) {
+			builder.append("{[1]=");
+		}
+
+		builder.append("true");
+		for (int i = 0; i < depth; i++) {
+			builder.append("}");
+		}
+
+		return builder.toString();
+	}
+
+	@SuppressWarnings("unchecked")
+	public static void testCopyNestedTables() {
+		Map<Object, Object> inner = new HashMap<Object, Object>();
+		inner.put("x", new Double(1));
+		Map<Object, Object> outer = new HashMap<Object, Object>();
+		outer.put("inner", inner);
+		outer.put(new Double(1), "one");
+
+		Map<Object, Object> copy = (Map<Object, Object>) Serializer.copy(outer);
+		Tests.checkEqual(outer, copy);
+		Tests.check(copy != outer, "outer table wasn't copied");
+		Tests.check(copy.get("inner") != inner, "inner table wasn't copied");
+
+		// Changing the copy leaves the original alone
+		((Map<Object, Object>) copy.get("inner")).put("x", new Double(2));
+		Tests.checkEqual(new Double(1), inner.get("x"));
+	}
+
+	public static void testCopySharedTables() {
+		Map<Object, Object> shared = new HashMap<Object, Object>();
+		Map<Object, Object> outer = new HashMap<Object, Object>();
+		outer.put("a", shared);
+		outer.put("b", shared);
+
+		Map<?, ?> copy = (Map<?, ?>) Serializer.copy(outer);
+		Tests.check(copy.get("a") == copy.get("b"), "shared table copied twice");
+		Tests.check(copy.get("a") != shared, "shared table wasn't copied");
+	}
+
+	public static void testCopySelfReferencingTable() {
+		Map<Object, Object> table = new HashMap<Object, Object>();
+		table.put("self", table);
+		table.put("name", "loop");
+
+		Map<?, ?> copy = (Map<?, ?>) Serializer.copy(table);
+		Tests.check(copy != table, "table wasn't copied");
+		Tests.check(copy.get("self") == copy, "copy doesn't refer to itself");
+		Tests.checkEqual("loop", copy.get("name"));
+	}
+
+	public static void testCopyUnsupportedValues() {
+		// Functions and other objects can't be sent, and become nil
+		Object function = new Object();
+		Tests.checkEqual(null, Serializer.copy(function));
+
+		Map<Object, Object> table = new HashMap<Object, Object>();
+		table.put("callback", function);
+		table.put(function, "key");
+		table.put("kept", Boolean.TRUE);
+
+		Map<?, ?> copy = (Map<?, ?>) Serializer.copy(table);
+		Tests.checkEqual(1, copy.size());
+		Tests.checkEqual(Boolean.TRUE, copy.get("kept"));
+	}
+
+	public static void testCopyNumbers() {
+		Tests.checkEqual(new Double(3), Serializer.copy(new Integer(3)));
+		Tests.checkEqual(null, Serializer.copy(null));
+	}
+
+	public static void testRoundTrip() {
+		Map<Object, Object> table = new HashMap<Object, Object>();
+		table.put(new Double(1), "line\none \"quoted\"");
+		table.put("to", new Double(5.5));
+		table.put("ok", Boolean.TRUE);
+
+		Tests.checkEqual(table, Serializer.parse(Serializer.serialize(table)));
+	}
+
+	public static void testSpecialNumbers() {
+		Tests.checkEqual("0/0", Serializer.serialize(new Double(Double.NaN)));
+		Tests.checkEqual(new Double(Double.NEGATIVE_INFINITY), Serializer.parse("-1/0"));
+	}
+
+	public static void testNestingLimit() {
+		Serializer.parse(nested(Serializer.MAX_DEPTH));
+
+		try {
+			Serializer.parse(nested(100000));
+			Tests.check(false, "deeply nested message was parsed");
+		} catch (IllegalArgumentException e) {
+			// Rejected without overflowing the stack
+		}
+	}
+
+	public static void testInvalid() {
+		String[] invalid = new String[] {"", "{", "{[1]=}", "\"unfinished", "nope", "1 2"};
+		for (String text : invalid) {
+			try {
+				Serializer.parse(text);
+				Tests.check(false, "parsed invalid message `" + text + "`");
+			} catch (IllegalArgumentException e) {
+				// Expected
+			}
+		}
+	}
+
+}
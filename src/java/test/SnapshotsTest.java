@@ -0,0 +1,113 @@
+/*
+ * Snapshots Tests
+ */
+
+
+import java.io.File;
+import java.io.IOException;
+
+
+public class SnapshotsTest {
+
+	public static void testTakeAndRestore() throws Exception {
+		File files = new File(Tests.directory(), "0");
+		File snapshots = Tests.directory();
+		Tests.write(new File(files, "program"), "first");
+
+		Tests.checkEqual("1", Snapshots.take(files, snapshots));
+		Tests.write(new File(files, "program"), "second");
+		Tests.write(new File(files, "extra/file"), "extra");
+		Tests.checkEqual("2", Snapshots.take(files, snapshots));
+
+		Snapshots.restore(new File(snapshots, "1"), files);
+		Tests.checkEqual("first\n", Tests.read(new File(files, "program")));
+		Tests.check(!new File(files, "extra").exists(), "newer files kept");
+		Tests.check(!new File(files.getPath() + ".rollback").exists(), "staging left");
+
+		Snapshots.restore(new File(snapshots, "2"), files);
+		Tests.checkEqual("second\n", Tests.read(new File(files, "program")));
+		Tests.checkEqual("extra\n", Tests.read(new File(files, "extra/file")));
+	}
+
+	public static void testInsideLink() throws Exception {
+		File parent = Tests.linkedDirectory();
+		File files = new File(parent, "0");
+		File snapshots = new File(parent, "snapshots");
+		Tests.write(new File(files, "program"), "first");
+		Snapshots.take(files, snapshots);
+
+		Tests.write(new File(files, "extra/file"), "extra");
+		Snapshots.restore(new File(snapshots, "1"), files);
+		Tests.checkEqual("first\n", Tests.read(new File(files, "program")));
+		Tests.check(!new File(files, "extra").exists(), "newer files kept");
+
+		Tests.write(new File(snapshots, "2/nested/file"), "contents");
+		Snapshots.delete(new File(snapshots, "2"));
+		Tests.check(!new File(snapshots, "2").exists(), "snapshot not deleted");
+	}
+
+	public static void testTakeWithoutFiles() throws Exception {
+		File snapshots = Tests.directory();
+		Tests.checkEqual("1", Snapshots.take(new File(Tests.directory(), "none"), snapshots));
+		Tests.check(new File(snapshots, "1").isDirectory(), "snapshot not created");
+	}
+
+	public static void testLatest() throws Exception {
+		File snapshots = Tests.directory();
+		Tests.checkEqual(0, Snapshots.latest(snapshots));
+		Tests.checkEqual(0, Snapshots.latest(new File(snapshots, "missing")));
+
+		new File(snapshots, "2").mkdirs();
+		new File(snapshots, "10").mkdirs();
+		new File(snapshots, "3").mkdirs();
+		Tests.checkEqual(10, Snapshots.latest(snapshots));
+
+		// Anything that isn't a numbered folder isn't a snapshot
+		new File(snapshots, "11.partial").mkdirs();
+		new File(snapshots, "notes").mkdirs();
+		new File(snapshots, "-4").mkdirs();
+		Tests.write(new File(snapshots, "12"), "not a folder");
+		Tests.checkEqual(10, Snapshots.latest(snapshots));
+
+		Tests.checkEqual("11", Snapshots.take(new File(snapshots, "none"), snapshots));
+		Tests.checkEqual(11, Snapshots.latest(snapshots));
+		Tests.check(!new File(snapshots, "11.partial").exists(), "partial snapshot left");
+	}
+
+	public static void testList() throws Exception {
+		File files = Tests.directory();
+		File snapshots = Tests.directory();
+		Tests.write(new File(files, "a"), "1234");
+		Snapshots.take(files, snapshots);
+		Tests.write(new File(files, "b/c"), "12345678");
+		Snapshots.take(files, snapshots);
+
+		String[] lines = Snapshots.list(snapshots).split("\n");
+		Tests.checkEqual(2, lines.length);
+		Tests.check(lines[0].startsWith("1\t") && lines[0].endsWith("\t4"), lines[0]);
+		Tests.check(lines[1].startsWith("2\t") && lines[1].endsWith("\t12"), lines[1]);
+	}
+
+	public static void testRestoreMissing() throws Exception {
+		File files = Tests.directory();
+		Tests.write(new File(files, "program"), "kept");
+
+		try {
+			Snapshots.restore(new File(Tests.directory(), "1"), files);
+			Tests.check(false, "restored a missing snapshot");
+		} catch (IOException e) {
+			Tests.checkEqual("No snapshot named 1", e.getMessage());
+		}
+
+		Tests.checkEqual("kept\n", Tests.read(new File(files, "program")));
+	}
+
+	public static void testDelete() throws Exception {
+		File snapshots = Tests.directory();
+		Tests.write(new File(snapshots, "1/nested/file"), "contents");
+		Snapshots.delete(new File(snapshots, "1"));
+		Tests.check(!new File(snapshots, "1").exists(), "snapshot not deleted");
+		Tests.checkEqual(0, Snapshots.latest(snapshots));
+	}
+
+}
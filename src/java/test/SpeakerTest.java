@@ -0,0 +1,69 @@
+/*
+ * Speaker Tests
+ */
+
+
+import java.io.File;
+
+
+public class SpeakerTest {
+
+	private static Object[] harp() {
+		return new Object[] {"harp", new Double(1.0), new Double(12.0)};
+	}
+
+	private static boolean play(Speaker speaker) throws Exception {
+		Object[] result = speaker.callMethod(null, null, 0, harp());
+		return ((Boolean) result[0]).booleanValue();
+	}
+
+	public static void testNotesAreTimedInTicks() throws Exception {
+		File directory = Tests.directory();
+		Speaker speaker = new Speaker(directory.getPath(), "test", false);
+
+		play(speaker);
+		speaker.advance(0.5);
+		play(speaker);
+		speaker.advance(0.26);
+		play(speaker);
+
+		String[] lines = Tests.read(new File(directory, "test.txt")).split("\n");
+		Tests.checkEqual(3, lines.length);
+		Tests.checkEqual("0", lines[0].split("\t")[0]);
+		Tests.checkEqual("10", lines[1].split("\t")[0]);
+		Tests.checkEqual("15", lines[2].split("\t")[0]);
+	}
+
+	public static void testNotesPerTickAreLimited() throws Exception {
+		Speaker speaker = new Speaker(Tests.directory().getPath(), "test", false);
+		for (int i = 0; i < 8; i++) {
+			Tests.check(play(speaker), "note " + i + " should play");
+		}
+
+		Tests.check(!play(speaker), "ninth note in a tick should be dropped");
+		speaker.advance(0.05);
+		Tests.check(play(speaker), "note on the next tick should play");
+	}
+
+	public static void testSavedOnceQuiet() throws Exception {
+		File directory = Tests.directory();
+		Speaker speaker = new Speaker(directory.getPath(), "test", false);
+		File wav = new File(directory, "test.wav");
+
+		play(speaker);
+		speaker.advance(0.5);
+		Tests.check(!wav.exists(), "saved while still playing");
+		speaker.advance(0.5);
+		Tests.check(wav.exists(), "not saved after a second of silence");
+	}
+
+	public static void testSavedOnClose() throws Exception {
+		File directory = Tests.directory();
+		Speaker speaker = new Speaker(directory.getPath(), "test", false);
+
+		play(speaker);
+		speaker.close();
+		Tests.check(new File(directory, "test.wav").exists(), "not saved on close");
+	}
+
+}
@@ -0,0 +1,127 @@
+/*
+ * Tests
+ *
+ * Runs every public static method whose name starts with `test` in each of
+ * the classes named on the command line, printing any failures.
+ */
+
+
+import java.io.BufferedReader;
+import java.io.File;
+import java.io.FileReader;
+import java.io.FileWriter;
+import java.io.IOException;
+import java.lang.reflect.InvocationTargetException;
+import java.lang.reflect.Method;
+import java.lang.reflect.Modifier;
+import java.nio.file.Files;
+
+
+public class Tests {
+
+	public static void main(String[] arguments) throws ClassNotFoundException {
+		int count = 0;
+		int failures = 0;
+
+		for (String name : arguments) {
+			for (Method method : Class.forName(name).getMethods()) {
+				if (!method.getName().startsWith("test") ||
+						!Modifier.isStatic(method.getModifiers())) {
+					continue;
+				}
+
+				count++;
+				try {
+					method.invoke(null);
+				} catch (InvocationTargetException e) {
+					failures++;
+					System.out.println("FAILED " + name + "." + method.getName() + ": " +
+						e.getCause());
+				} catch (IllegalAccessException e) {
+					failures++;
+					System.out.println("FAILED " + name + "." + method.getName() + ": " + e);
+				}
+			}
+		}
+
+		System.out.println((count - failures) + " of " + count + " tests passed");
+		if (failures > 0) {
+			System.exit(1);
+		}
+	}
+
+
+	/*
+	 * Assertions
+	 */
+
+	public static void check(boolean condition, String message) {
+		if (!condition) {
+			throw new AssertionError(message);
+		}
+	}
+
+	public static void checkEqual(Object expected, Object actual) {
+		boolean equal = expected == null ? actual == null : expected.equals(actual);
+		if (!equal) {
+			throw new AssertionError("expected <" + expected + "> but was <" + actual + ">");
+		}
+	}
+
+
+	/*
+	 * Files
+	 */
+
+	/*
+	 * Returns a new, empty temporary directory.
+	 */
+	public static File directory() throws IOException {
+		File directory = File.createTempFile("mimic", "test");
+		directory.delete();
+		directory.mkdirs();
+		return directory;
+	}
+
+	/*
+	 * Returns a new, empty temporary directory inside a link to another
+	 * folder, like the temporary folder on macOS.
+	 */
+	public static File linkedDirectory() throws IOException {
+		File link = new File(directory(), "link");
+		Files.createSymbolicLink(link.toPath(), directory().toPath());
+
+		File directory = new File(link, "directory");
+		directory.mkdirs();
+		return directory;
+	}
+
+	/*
+	 * Writes a file, creating any folders it's in.
+	 */
+	public static void write(File file, String contents) throws IOException {
+		file.getParentFile().mkdirs();
+		FileWriter writer = new FileWriter(file);
+		writer.write(contents);
+		writer.close();
+	}
+
+	/*
+	 * Returns the contents of a file.
+	 */
+	public static String read(File file) throws IOException {
+		StringBuilder contents = new StringBuilder();
+		BufferedReader reader = new BufferedReader(new FileReader(file));
+		try {
+			String line;
+			while ((line = reader.readLine()) != null) {
+				contents.append(line).append("\n");
+			}
+		} finally {
+			reader.close();
+		}
+
+		return contents.toString();
+	}
+
+}
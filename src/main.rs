@@ -9,7 +9,10 @@ extern crate terminal;
 extern crate jni;
 extern crate serialize;
 
+use std::os;
+
 use emulator::Emulator;
+use minion::Kind;
 use config::Config;
 use error::ErrorWindow;
 
@@ -20,6 +23,9 @@ mod convert;
 mod storage;
 mod config;
 mod error;
+mod command;
+mod control;
+mod viewer;
 
 
 fn main() {
@@ -30,10 +36,33 @@ fn main() {
 	let potential = Config::from_file(&storage::config());
 
 	match potential {
-		Ok(config) => {
+		Ok(ref config) if is_control() => {
+			// Send a command to an already running emulator.
+			let line = os::args().slice_from(2).connect(" ");
+			if config.control_port == 0 {
+				println!("Set the `control port` option to control a running emulator.");
+			} else if let Err(message) = control::send(config.control_port, line.as_slice()) {
+				println!("{}", message);
+			}
+		},
+		Ok(mut config) => {
+			// Use the workspace chosen on the command line.
+			if let Some(workspace) = option("--workspace") {
+				let path = os::make_absolute(&Path::new(workspace)).unwrap();
+				config.workspace = path.as_str().unwrap().to_string();
+			}
+
+			// Use the ComputerCraft jar chosen on the command line.
+			if let Some(name) = option("--computercraft") {
+				if let Err(message) = config.select_computercraft(name.as_slice()) {
+					println!("{}", message);
+					return;
+				}
+			}
+
 			// Successfully loaded. Start the emulator.
 			let mut emulator = Emulator::new(&config);
-			emulator.new_minion(true, false);
+			emulator.new_minion(true, Kind::Computer);
 			emulator.run();
 		},
 		Err(message) => {
@@ -51,3 +80,19 @@ fn main() {
 		}
 	}
 }
+
+
+/// Returns the value following a command line option, such as `--option
+/// value`.
+fn option(name: &str) -> Option<String> {
+	let args = os::args();
+	args.iter().position(|arg| arg.as_slice() == name)
+		.and_then(|index| args.get(index + 1))
+		.map(|value| value.clone())
+}
+
+/// Returns true if Mimic was run as `mimic control <command>`.
+fn is_control() -> bool {
+	let args = os::args();
+	args.len() > 2 && args[1].as_slice() == "control"
+}
@@ -39,6 +39,19 @@ const VALID_CHARACTERS: &'static str = concat!(
 	"pqrstuvwxyz{|}~",
 );
 
+/// The names of each side of a computer.
+pub const SIDES: [&'static str; 6] = ["bottom", "top", "back", "front", "right", "left"];
+
+/// The strongest redstone signal.
+pub const MAX_REDSTONE: u8 = 15;
+
+/// The width of the redstone control panel in cells, including its border.
+const PANEL_WIDTH: u32 = 42;
+
+/// The column of the first of the sixteen bundled color cells on the redstone
+/// control panel.
+const PANEL_COLORS_X: u32 = 25;
+
 
 /// An action for the emulator to perform.
 #[derive(PartialEq)]
@@ -48,6 +61,56 @@ pub enum Action {
 
 	/// Arguments: advanced
 	NewPocketComputer(bool),
+
+	/// Arguments: advanced
+	NewTurtle(bool),
+
+	NewCommandComputer,
+
+	/// Arguments: turtle ID
+	ViewWorld(u32),
+
+	/// Arguments: computer ID
+	Snapshot(u32),
+
+	/// Arguments: computer ID
+	Rollback(u32),
+}
+
+
+/// The type of computer a minion emulates.
+#[derive(Clone, Copy, PartialEq, Show)]
+pub enum Kind {
+	Computer,
+	Pocket,
+	Turtle,
+	Command,
+}
+
+
+impl Kind {
+
+	/// Returns the kind's name, as stored in exported archives.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			Kind::Computer => "computer",
+			Kind::Pocket => "pocket",
+			Kind::Turtle => "turtle",
+			Kind::Command => "command",
+		}
+	}
+
+	/// Returns the kind with the given name.
+	pub fn from_name(name: &str) -> Option<Kind> {
+		match name {
+			"computer" => Some(Kind::Computer),
+			"pocket" => Some(Kind::Pocket),
+			"turtle" => Some(Kind::Turtle),
+			"command" => Some(Kind::Command),
+			_ => None,
+		}
+	}
+
 }
 
 
@@ -55,6 +118,7 @@ pub enum Action {
 #[derive(Clone, Show)]
 pub struct Options {
 	pub id: u32,
+	pub kind: Kind,
 	pub advanced: bool,
 	pub title: String,
 	pub width: u32,
@@ -62,6 +126,7 @@ pub struct Options {
 	pub space_limit: u64,
 	pub border_width: u32,
 	pub border_height: u32,
+	pub live_audio: bool,
 }
 
 
@@ -69,6 +134,8 @@ pub struct Options {
 pub struct Minion {
 	pub term: Terminal,
 	java_object: Object,
+	id: u32,
+	pub kind: Kind,
 
 	cursor_flash: bool,
 	cursor_flash_swap_time: f64,
@@ -78,10 +145,14 @@ pub struct Minion {
 	shortcut_timer: f64,
 	shortcut_key: Key,
 
-	modem_attached: bool,
 	previous_drag_x: i32,
 	previous_drag_y: i32,
 	advance_time: f64,
+
+	redstone_outputs: [u8; 6],
+	bundled_outputs: [u16; 6],
+	outputs_changed: bool,
+	panel_visible: bool,
 }
 
 
@@ -127,12 +198,15 @@ impl Minion {
 			Value::String(storage_dir),
 			Value::String(rom_dir),
 			Value::Long(options.space_limit as i64),
+			Value::Boolean(options.live_audio),
 		]).unwrap();
 
 		let current_time = time();
 		Minion {
 			term: term,
 			java_object: java_object,
+			id: options.id,
+			kind: options.kind,
 
 			cursor_flash: true,
 			cursor_flash_swap_time: current_time,
@@ -142,10 +216,14 @@ impl Minion {
 			shortcut_timer: -1.0,
 			shortcut_key: Key::A,
 
-			modem_attached: false,
 			previous_drag_x: -1,
 			previous_drag_y: -1,
 			advance_time: current_time,
+
+			redstone_outputs: [0; 6],
+			bundled_outputs: [0; 6],
+			outputs_changed: false,
+			panel_visible: false,
 		}
 	}
 
@@ -182,7 +260,7 @@ impl Minion {
 			.unwrap().to_i32();
 		let blink = self.java_object.call("getCursorBlink", &[], Type::Boolean)
 			.unwrap().to_bool();
-		let visible = blink && self.cursor_flash;
+		let visible = blink && self.cursor_flash && !self.panel_visible;
 		let hex_color = color::number_to_hex(color);
 
 		self.term.cursor_visibility(visible);
@@ -227,6 +305,117 @@ impl Minion {
 		}
 	}
 
+	/// Fetch any redstone outputs that changed since the last frame. Java only
+	/// reports the sides that changed, as lines of `side\tlevel\tcolors`.
+	fn update_redstone(&mut self) {
+		let changes = self.java_object.call("getChangedOutputs", &[],
+			Type::String).unwrap().to_string();
+
+		for line in changes.as_slice().lines() {
+			let fields: Vec<&str> = line.split('\t').collect();
+			if fields.len() != 3 {
+				continue;
+			}
+
+			let index = match SIDES.iter().position(|side| *side == fields[0]) {
+				Some(index) => index,
+				None => continue,
+			};
+
+			self.redstone_outputs[index] = fields[1].parse::<u8>().unwrap_or(0);
+			self.bundled_outputs[index] = fields[2].parse::<u16>().unwrap_or(0);
+			self.outputs_changed = true;
+		}
+	}
+
+	/// Write a string into the window's cells with the given colors, clipped to
+	/// the window's width.
+	fn draw_text(&mut self, text: &str, x: u32, y: u32, foreground: u32, background: u32) {
+		for (offset, character) in text.chars().enumerate() {
+			let cell_x = x + offset as u32;
+			if cell_x >= self.width {
+				break;
+			}
+
+			self.term.character(character, cell_x, y);
+			self.term.foreground(foreground, cell_x, y);
+			self.term.background(background, cell_x, y);
+		}
+	}
+
+	/// Draw the redstone control panel over the top left of the terminal,
+	/// showing the input and output on each side. Bundled colors are shown as
+	/// hex numbers, followed by a cell for each bundled input color, filled in
+	/// when the color is on.
+	fn draw_panel(&mut self) {
+		let header = format!(" {:<6} {:>2} {:>3} {:>4} {:>4} {:<17}", "Side", "In", "Out",
+			"BIn", "BOut", "Colors");
+		self.draw_text(header.as_slice(), 0, 0, color::BLACK, color::LIGHT_GRAY);
+
+		for (index, side) in SIDES.iter().enumerate() {
+			let y = index as u32 + 1;
+			if y >= self.height {
+				break;
+			}
+
+			let input = self.redstone_input(*side);
+			let output = self.redstone_outputs[index];
+			let bundled_input = self.bundled_input(*side);
+			let bundled_output = self.bundled_outputs[index];
+
+			let name = format!(" {:<6} ", side);
+			let input_text = format!("{:>2}", input);
+			let output_text = format!(" {:>3}", output);
+			let bundled = format!(" {:04X} {:04X} ", bundled_input, bundled_output);
+
+			let input_color = if input > 0 { color::RED } else { color::GRAY };
+			let output_color = if output > 0 { color::RED } else { color::GRAY };
+
+			self.draw_text(name.as_slice(), 0, y, color::WHITE, color::GRAY);
+			self.draw_text(input_text.as_slice(), 8, y, color::WHITE, input_color);
+			self.draw_text(output_text.as_slice(), 10, y, color::WHITE, output_color);
+			self.draw_text(bundled.as_slice(), 14, y, color::WHITE, color::GRAY);
+
+			for bit in range(0, 16) {
+				// Bit 0 is white, which is color number 15
+				let cell_color = color::number_to_hex(15 - bit as i32);
+				let x = PANEL_COLORS_X + bit;
+				if bundled_input & (1 << bit as usize) != 0 {
+					self.draw_text(" ", x, y, color::WHITE, cell_color);
+				} else {
+					self.draw_text(".", x, y, cell_color, color::GRAY);
+				}
+			}
+			self.draw_text(" ", PANEL_COLORS_X + 16, y, color::WHITE, color::GRAY);
+		}
+	}
+
+	/// Returns true if the redstone control panel is visible and covers a
+	/// cell.
+	fn panel_contains(&self, cell_x: u32, cell_y: u32) -> bool {
+		self.panel_visible && cell_x < PANEL_WIDTH && (cell_y as usize) <= SIDES.len()
+	}
+
+	/// Returns the side shown on the redstone control panel at a cell, if the
+	/// panel is visible.
+	fn panel_side(&self, cell_x: u32, cell_y: u32) -> Option<&'static str> {
+		if self.panel_contains(cell_x, cell_y) && cell_y >= 1 {
+			Some(SIDES[cell_y as usize - 1])
+		} else {
+			None
+		}
+	}
+
+	/// Returns the bundled color shown on the redstone control panel at a
+	/// column, if any.
+	fn panel_color(&self, cell_x: u32) -> Option<u16> {
+		if cell_x >= PANEL_COLORS_X && cell_x < PANEL_COLORS_X + 16 {
+			Some(1 << (cell_x - PANEL_COLORS_X) as usize)
+		} else {
+			None
+		}
+	}
+
 	/// Update the contents of the window's cells and advance the computer's tick count.
 	pub fn advance(&mut self) {
 		// Advance
@@ -255,6 +444,14 @@ impl Minion {
 			self.update_line(y, text.as_slice(), color.as_slice());
 		}
 
+		// Fetch changes to redstone outputs
+		self.update_redstone();
+
+		// Draw the redstone control panel on top
+		if self.panel_visible {
+			self.draw_panel();
+		}
+
 		// Update the cursor's position, visibility, and color
 		self.update_cursor();
 
@@ -340,9 +537,32 @@ impl Minion {
 				Key::B =>
 					(Some(Action::NewPocketComputer(true)), true),
 
+				// New normal turtle
+				Key::U if shift_down =>
+					(Some(Action::NewTurtle(false)), true),
+
+				// New advanced turtle
+				Key::U =>
+					(Some(Action::NewTurtle(true)), true),
+
+				// New command computer
+				Key::J =>
+					(Some(Action::NewCommandComputer), true),
+
+				// Attach an ender modem
+				Key::A if shift_down => {
+					if self.has_peripheral("top") {
+						self.detach_modem();
+					} else {
+						self.attach_ender_modem();
+					}
+
+					(None, true)
+				},
+
 				// Attach a modem
 				Key::A => {
-					if self.modem_attached {
+					if self.has_peripheral("top") {
 						self.detach_modem();
 					} else {
 						self.attach_modem();
@@ -351,6 +571,52 @@ impl Minion {
 					(None, true)
 				},
 
+				// Attach a speaker, on the back of pocket computers
+				Key::M => {
+					let side = if self.kind == Kind::Pocket { "back" } else { "left" };
+					if self.has_speaker(side) {
+						self.detach_speaker(side);
+					} else {
+						self.attach_speaker(side);
+					}
+
+					(None, true)
+				},
+
+				// Toggle a redstone input
+				Key::Number1 | Key::Number2 | Key::Number3 |
+				Key::Number4 | Key::Number5 | Key::Number6 => {
+					let side = SIDES[match key {
+						Key::Number1 => 0,
+						Key::Number2 => 1,
+						Key::Number3 => 2,
+						Key::Number4 => 3,
+						Key::Number5 => 4,
+						_ => 5,
+					}];
+
+					self.toggle_redstone_input(side);
+					(None, true)
+				},
+
+				// Open the world view for a turtle
+				Key::G if self.kind == Kind::Turtle =>
+					(Some(Action::ViewWorld(self.id)), true),
+
+				// Roll back to the latest snapshot
+				Key::K if shift_down =>
+					(Some(Action::Rollback(self.id)), true),
+
+				// Take a snapshot of the computer's files
+				Key::K =>
+					(Some(Action::Snapshot(self.id)), true),
+
+				// Show or hide the redstone control panel
+				Key::P => {
+					self.panel_visible = !self.panel_visible;
+					(None, true)
+				},
+
 				// Close the window
 				Key::W => {
 					self.term.window.close();
@@ -391,11 +657,24 @@ impl Minion {
 		}
 	}
 
-	/// Trigger a mouse click event.
-	pub fn trigger_mouse_click(&self, x: f32, y: f32, button: MouseButton) {
+	/// Trigger a mouse click event, or toggle a redstone input or bundled
+	/// color if the click is on the control panel.
+	pub fn trigger_mouse_click(&mut self, x: f32, y: f32, button: MouseButton) {
 		let converted_button = convert::button_to_lwjgl(button);
 		let (cell_x, cell_y) = self.term.to_cell_position(x, y);
 
+		if let Some(side) = self.panel_side(cell_x as u32, cell_y as u32) {
+			match self.panel_color(cell_x as u32) {
+				Some(color) => self.toggle_bundled_input(side, color),
+				None => self.toggle_redstone_input(side),
+			}
+			return;
+		}
+
+		if self.panel_contains(cell_x as u32, cell_y as u32) {
+			return;
+		}
+
 		self.java_object.call("mouseClickEvent", &[
 			Value::Int(converted_button),
 			Value::Int(cell_x as i32 + 1),
@@ -403,10 +682,14 @@ impl Minion {
 		], Type::Void).unwrap();
 	}
 
-	/// Trigger a mouse drag event.
+	/// Trigger a mouse drag event, unless it's on the control panel.
 	pub fn trigger_mouse_drag(&mut self, x: f32, y: f32, button: MouseButton) {
 		let converted_button = convert::button_to_lwjgl(button);
 		let (cell_x, cell_y) = self.term.to_cell_position(x, y);
+		if self.panel_contains(cell_x as u32, cell_y as u32) {
+			return;
+		}
+
 		let rx = cell_x as i32 + 1;
 		let ry = cell_y as i32 + 1;
 
@@ -422,11 +705,15 @@ impl Minion {
 		}
 	}
 
-	/// Trigger a mouse scroll event.
+	/// Trigger a mouse scroll event, unless it's on the control panel.
 	pub fn trigger_scroll(&self, y_delta: f32) {
 		let x = self.term.window.cursor_x();
 		let y = self.term.window.cursor_y();
 		let (cell_x, cell_y) = self.term.to_cell_position(x, y);
+		if self.panel_contains(cell_x as u32, cell_y as u32) {
+			return;
+		}
+
 		let direction = if y_delta < 0.0 { 1 } else { -1 };
 
 		self.java_object.call("mouseScrollEvent", &[
@@ -444,13 +731,169 @@ impl Minion {
 	/// Attach a modem on the minion.
 	pub fn attach_modem(&mut self) {
 		self.java_object.call("attachModem", &[], Type::Void).unwrap();
-		self.modem_attached = true;
+	}
+
+	/// Attach an ender modem, with unlimited range, on the minion.
+	pub fn attach_ender_modem(&mut self) {
+		self.java_object.call("attachEnderModem", &[], Type::Void).unwrap();
 	}
 
 	/// Detach the modem from the minion.
 	pub fn detach_modem(&mut self) {
 		self.java_object.call("detachModem", &[], Type::Void).unwrap();
-		self.modem_attached = false;
+	}
+
+	/// Attach a wired modem connected to the named cable network.
+	pub fn attach_wired_modem(&mut self, side: &str, cable: &str) {
+		self.java_object.call("attachWiredModem", &[
+			Value::String(side.to_string()),
+			Value::String(cable.to_string()),
+		], Type::Void).unwrap();
+	}
+
+	/// Attach a speaker on the given side of the minion.
+	pub fn attach_speaker(&mut self, side: &str) {
+		self.java_object.call("attachSpeaker", &[
+			Value::String(side.to_string()),
+		], Type::Void).unwrap();
+	}
+
+	/// Detach the speaker on the given side of the minion, writing out its
+	/// audio.
+	pub fn detach_speaker(&mut self, side: &str) {
+		self.java_object.call("detachSpeaker", &[
+			Value::String(side.to_string()),
+		], Type::Void).unwrap();
+	}
+
+	/// Returns true if there's a speaker on the given side.
+	pub fn has_speaker(&self, side: &str) -> bool {
+		self.java_object.call("hasSpeaker", &[
+			Value::String(side.to_string()),
+		], Type::Boolean).unwrap().to_bool()
+	}
+
+	/// Place the computer with the given ID on a side, as a `computer`
+	/// peripheral. Returns false if there's no computer with that ID.
+	pub fn attach_computer(&mut self, side: &str, id: u32) -> bool {
+		self.java_object.call("attachComputer", &[
+			Value::String(side.to_string()),
+			Value::Int(id as i32),
+		], Type::Boolean).unwrap().to_bool()
+	}
+
+	/// Returns true if there's a peripheral attached on the given side.
+	pub fn has_peripheral(&self, side: &str) -> bool {
+		self.java_object.call("hasPeripheral", &[
+			Value::String(side.to_string()),
+		], Type::Boolean).unwrap().to_bool()
+	}
+
+	/// Detach whatever peripheral is on the given side.
+	pub fn detach_peripheral(&mut self, side: &str) {
+		self.java_object.call("detachPeripheral", &[
+			Value::String(side.to_string()),
+		], Type::Void).unwrap();
+	}
+
+	/// Set the network used by all wireless modems on the minion.
+	pub fn set_network(&mut self, network: &str) {
+		self.java_object.call("setNetwork", &[
+			Value::String(network.to_string()),
+		], Type::Void).unwrap();
+	}
+
+	/// Move the wireless modem on the given side onto a network, returning
+	/// false if there's no wireless modem on that side.
+	pub fn set_modem_network(&mut self, side: &str, network: &str) -> bool {
+		self.java_object.call("setModemNetwork", &[
+			Value::String(side.to_string()),
+			Value::String(network.to_string()),
+		], Type::Boolean).unwrap().to_bool()
+	}
+
+	/// Move the minion to a position in the world.
+	pub fn set_position(&mut self, x: f64, y: f64, z: f64) {
+		self.java_object.call("setPosition", &[
+			Value::Double(x),
+			Value::Double(y),
+			Value::Double(z),
+		], Type::Void).unwrap();
+	}
+
+	/// Set the redstone signal strength, from 0 to 15, entering a side.
+	pub fn set_redstone_input(&mut self, side: &str, level: u8) {
+		self.java_object.call("setRedstoneInput", &[
+			Value::String(side.to_string()),
+			Value::Int(level as i32),
+		], Type::Void).unwrap();
+	}
+
+	/// Returns the redstone signal strength entering a side.
+	pub fn redstone_input(&self, side: &str) -> u8 {
+		self.java_object.call("getRedstoneInput", &[
+			Value::String(side.to_string()),
+		], Type::Int).unwrap().to_i32() as u8
+	}
+
+	/// Switch the redstone input on a side fully on if it's off, or off
+	/// otherwise.
+	pub fn toggle_redstone_input(&mut self, side: &str) {
+		let level = if self.redstone_input(side) == 0 { MAX_REDSTONE } else { 0 };
+		self.set_redstone_input(side, level);
+	}
+
+	/// Switch a single bundled color entering a side on if it's off, or off
+	/// otherwise.
+	pub fn toggle_bundled_input(&mut self, side: &str, color: u16) {
+		let colors = self.bundled_input(side) ^ color;
+		self.set_bundled_input(side, colors);
+	}
+
+	/// Set the colors entering a side through bundled cable.
+	pub fn set_bundled_input(&mut self, side: &str, colors: u16) {
+		self.java_object.call("setBundledInput", &[
+			Value::String(side.to_string()),
+			Value::Int(colors as i32),
+		], Type::Void).unwrap();
+	}
+
+	/// Returns the colors entering a side through bundled cable.
+	pub fn bundled_input(&self, side: &str) -> u16 {
+		self.java_object.call("getBundledInput", &[
+			Value::String(side.to_string()),
+		], Type::Int).unwrap().to_i32() as u16
+	}
+
+	/// Returns the redstone signal strength the computer emits from a side.
+	pub fn redstone_output(&self, side: &str) -> u8 {
+		self.java_object.call("getRedstoneOutput", &[
+			Value::String(side.to_string()),
+		], Type::Int).unwrap().to_i32() as u8
+	}
+
+	/// Returns the colors the computer emits from a side through bundled cable.
+	pub fn bundled_output(&self, side: &str) -> u16 {
+		self.java_object.call("getBundledOutput", &[
+			Value::String(side.to_string()),
+		], Type::Int).unwrap().to_i32() as u16
+	}
+
+	/// Returns the redstone signal strength and bundled colors the computer
+	/// emitted from a side as of the last frame, without asking Java.
+	pub fn outputs(&self, side: &str) -> (u8, u16) {
+		match SIDES.iter().position(|existing| *existing == side) {
+			Some(index) => (self.redstone_outputs[index], self.bundled_outputs[index]),
+			None => (0, 0),
+		}
+	}
+
+	/// Returns true if any redstone outputs changed since the last time this
+	/// was called.
+	pub fn take_outputs_changed(&mut self) -> bool {
+		let changed = self.outputs_changed;
+		self.outputs_changed = false;
+		changed
 	}
 
 	/// Paste the current clipboard contents string.
@@ -459,6 +902,165 @@ impl Minion {
 		self.java_object.call("paste", &[Value::String(contents)], Type::Void).unwrap();
 	}
 
+	/// Set the directory holding the computer's files. Takes effect the next
+	/// time the computer boots.
+	pub fn set_save_directory(&mut self, directory: &str) {
+		self.java_object.call("setSaveDirectory", &[
+			Value::String(directory.to_string()),
+		], Type::Void).unwrap();
+	}
+
+	/// Set whether the `rom` folder in the Mimic data folder is layered over
+	/// the computer's ROM. Takes effect the next time the computer boots.
+	pub fn set_global_rom(&mut self, enabled: bool) {
+		self.java_object.call("setGlobalRom", &[
+			Value::Boolean(enabled),
+		], Type::Void).unwrap();
+	}
+
+	/// Layer a directory over the computer's ROM, on top of any added before
+	/// it. Takes effect the next time the computer boots.
+	pub fn add_rom_layer(&mut self, directory: &str) {
+		self.java_object.call("addRomLayer", &[
+			Value::String(directory.to_string()),
+		], Type::Void).unwrap();
+	}
+
+	/// Overlay a read only startup program on the computer's files, used the
+	/// next time it boots.
+	pub fn set_startup(&mut self, program: &str) {
+		self.java_object.call("setStartup", &[
+			Value::String(program.to_string()),
+		], Type::Void).unwrap();
+	}
+
+	/// Mount a directory on the host at a path in the computer's files,
+	/// replacing any mount already at that path. Takes effect the next time
+	/// the computer boots.
+	pub fn add_mount(&mut self, path: &str, directory: &str, read_only: bool)
+			-> Result<(), String> {
+		let error = self.java_object.call("addMount", &[
+			Value::String(path.to_string()),
+			Value::String(directory.to_string()),
+			Value::Boolean(read_only),
+		], Type::String).unwrap().to_string();
+
+		result(error)
+	}
+
+	/// Remove the directory mounted at a path, returning false if there's
+	/// nothing mounted there. Takes effect the next time the computer boots.
+	pub fn remove_mount(&mut self, path: &str) -> bool {
+		self.java_object.call("removeMount", &[
+			Value::String(path.to_string()),
+		], Type::Boolean).unwrap().to_bool()
+	}
+
+	/// Returns the directories mounted in the computer, one per line, as tab
+	/// separated path, directory, and `ro` or `rw`.
+	pub fn mounts(&self) -> String {
+		self.java_object.call("getMounts", &[], Type::String).unwrap().to_string()
+	}
+
+	/// Make the minion a turtle in the shared world, facing north at its
+	/// position. Must be called before it's turned on.
+	pub fn make_turtle(&mut self, needs_fuel: bool) {
+		self.java_object.call("makeTurtle", &[
+			Value::Boolean(needs_fuel),
+		], Type::Void).unwrap();
+	}
+
+	/// Make the minion a pocket computer with the `pocket` API, equipping an
+	/// upgrade on its back, or none if it's empty, and keeping a list of
+	/// spare upgrades for `pocket.equipBack`.
+	pub fn make_pocket(&mut self, upgrade: &str, spares: &Vec<String>) -> Result<(), String> {
+		let error = self.java_object.call("makePocket", &[
+			Value::String(upgrade.to_string()),
+			Value::String(spares.connect("\n")),
+		], Type::String).unwrap().to_string();
+
+		result(error)
+	}
+
+	/// Give the minion the `commands` API of a command computer. Must be
+	/// called before it's turned on.
+	pub fn make_command_computer(&mut self) {
+		self.java_object.call("makeCommandComputer", &[], Type::Void).unwrap();
+	}
+
+	/// Set the fuel level of a turtle.
+	pub fn set_turtle_fuel(&mut self, fuel: u32) {
+		self.java_object.call("setTurtleFuel", &[
+			Value::Int(fuel as i32),
+		], Type::Void).unwrap();
+	}
+
+	/// Put items in a turtle's inventory slot, numbered from 1 to 16.
+	pub fn set_turtle_item(&mut self, slot: u32, name: &str, count: u32) {
+		self.java_object.call("setTurtleItem", &[
+			Value::Int(slot as i32),
+			Value::String(name.to_string()),
+			Value::Int(count as i32),
+		], Type::Void).unwrap();
+	}
+
+	/// Returns a turtle's block coordinates, separated by spaces.
+	pub fn turtle_position(&self) -> String {
+		self.java_object.call("getTurtlePosition", &[], Type::String).unwrap().to_string()
+	}
+
+	/// Returns a turtle's fuel level, or `unlimited`.
+	pub fn turtle_fuel(&self) -> String {
+		self.java_object.call("getTurtleFuel", &[], Type::String).unwrap().to_string()
+	}
+
+	/// Returns the direction a turtle is facing, such as `north`.
+	pub fn turtle_direction(&self) -> String {
+		self.java_object.call("getTurtleDirection", &[], Type::String).unwrap().to_string()
+	}
+
+	/// Returns a turtle's selected inventory slot, numbered from 1 to 16.
+	pub fn turtle_selected_slot(&self) -> u32 {
+		self.java_object.call("getTurtleSelectedSlot", &[], Type::Int).unwrap().to_i32() as u32
+	}
+
+	/// Returns the name of the item in a turtle's inventory slot, or an empty
+	/// string if the slot is empty.
+	pub fn turtle_item(&self, slot: u32) -> String {
+		self.java_object.call("getTurtleItem", &[
+			Value::Int(slot as i32),
+		], Type::String).unwrap().to_string()
+	}
+
+	/// Returns the number of items in a turtle's inventory slot.
+	pub fn turtle_item_count(&self, slot: u32) -> u32 {
+		self.java_object.call("getTurtleItemCount", &[
+			Value::Int(slot as i32),
+		], Type::Int).unwrap().to_i32() as u32
+	}
+
+	/// Returns how an item is drawn in the world view, as a character followed
+	/// by its text and background colors as hex digits.
+	pub fn item_symbol(&self, item: &str) -> String {
+		self.java_object.call("getItemSymbol", &[
+			Value::String(item.to_string()),
+		], Type::String).unwrap().to_string()
+	}
+
+	/// Draw the world around a turtle at its height, as lines of text, text
+	/// colors and background colors separated by tabs.
+	pub fn render_world(&self, width: u32, height: u32) -> String {
+		self.java_object.call("renderWorld", &[
+			Value::Int(width as i32),
+			Value::Int(height as i32),
+		], Type::String).unwrap().to_string()
+	}
+
+	/// Turn the computer on.
+	pub fn turn_on(&self) {
+		self.java_object.call("turnOn", &[], Type::Void).unwrap();
+	}
+
 	/// Terminate the current program on the computer.
 	pub fn terminate(&self) {
 		self.java_object.call("terminate", &[], Type::Void).unwrap();
@@ -469,9 +1071,100 @@ impl Minion {
 		self.java_object.call("shutdown", &[], Type::Void).unwrap();
 	}
 
+	/// Shut the computer down for good, saving any audio its speakers have
+	/// recorded.
+	pub fn destroy(&self) {
+		self.java_object.call("destroy", &[], Type::Void).unwrap();
+	}
+
 	/// Reboot the computer.
 	pub fn reboot(&self) {
 		self.java_object.call("reboot", &[], Type::Void).unwrap();
 	}
 
+	/// Set the computer's label, or remove it if the label is empty.
+	pub fn set_label(&mut self, label: &str) {
+		self.java_object.call("setLabel", &[
+			Value::String(label.to_string()),
+		], Type::Void).unwrap();
+	}
+
+	/// Copy the computer's files into a new snapshot, which becomes its latest.
+	pub fn take_snapshot(&self) -> Result<(), String> {
+		let error = self.java_object.call("takeSnapshot", &[], Type::String)
+			.unwrap().to_string();
+
+		result(error)
+	}
+
+	/// Returns one line for each of the computer's snapshots, oldest first,
+	/// with tab separated name, time taken and size in bytes.
+	pub fn snapshots(&self) -> String {
+		self.java_object.call("getSnapshots", &[], Type::String).unwrap().to_string()
+	}
+
+	/// Returns the name of the computer's most recent snapshot.
+	pub fn latest_snapshot(&self) -> Option<String> {
+		let name = self.java_object.call("getLatestSnapshot", &[], Type::String)
+			.unwrap().to_string();
+
+		if name.len() == 0 {
+			None
+		} else {
+			Some(name)
+		}
+	}
+
+	/// Replace the computer's files with those in a snapshot, shutting the
+	/// computer down meanwhile and booting it again afterwards if it's on.
+	pub fn rollback(&mut self, name: &str) -> Result<(), String> {
+		let error = self.java_object.call("rollback", &[
+			Value::String(name.to_string()),
+		], Type::String).unwrap().to_string();
+
+		result(error)
+	}
+
+	/// Delete one of the computer's snapshots.
+	pub fn delete_snapshot(&mut self, name: &str) -> Result<(), String> {
+		let error = self.java_object.call("deleteSnapshot", &[
+			Value::String(name.to_string()),
+		], Type::String).unwrap().to_string();
+
+		result(error)
+	}
+
+	/// Write the computer's files, label and type to a zip archive.
+	pub fn export_archive(&self, path: &str) -> Result<(), String> {
+		let error = self.java_object.call("exportArchive", &[
+			Value::String(path.to_string()),
+			Value::String(self.kind.name().to_string()),
+		], Type::String).unwrap().to_string();
+
+		result(error)
+	}
+
+	/// Replace the computer's files and label with those in a zip archive,
+	/// handling existing files as described by the mode, one of `fail`,
+	/// `replace` or `merge`.
+	pub fn import_archive(&mut self, path: &str, mode: &str) -> Result<(), String> {
+		let error = self.java_object.call("importArchive", &[
+			Value::String(path.to_string()),
+			Value::String(mode.to_string()),
+		], Type::String).unwrap().to_string();
+
+		result(error)
+	}
+
+}
+
+
+/// Converts the error message returned by a Java method, empty on success,
+/// into a result.
+pub fn result(error: String) -> Result<(), String> {
+	if error.len() == 0 {
+		Ok(())
+	} else {
+		Err(error)
+	}
 }
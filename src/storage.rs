@@ -102,12 +102,19 @@ pub fn config() -> Path {
 	path
 }
 
-/// Returns the classpath for the Java VM.
-pub fn classpath() -> Vec<Path> {
+/// Returns the classpath for the Java VM, using the given ComputerCraft jar,
+/// or the one in the resources folder.
+pub fn classpath(cc_jar: Option<Path>) -> Vec<Path> {
 	let dir = resources();
 
-	let mut cc_file = dir.clone();
-	cc_file.push(CC_JAR_FILE_NAME);
+	let cc_file = match cc_jar {
+		Some(path) => path,
+		None => {
+			let mut path = dir.clone();
+			path.push(CC_JAR_FILE_NAME);
+			path
+		},
+	};
 
 	let mut mimic_file = dir.clone();
 	mimic_file.push(MIMIC_JAR_FILE_NAME);
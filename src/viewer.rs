@@ -0,0 +1,146 @@
+//
+//  Viewer
+//! A window showing the world around a turtle, and its inventory.
+//
+
+
+use terminal::Terminal;
+
+use minion::Minion;
+use color;
+
+
+/// The width of the viewer window in cells.
+const WIDTH: u32 = 51;
+
+/// The height of the viewer window in cells.
+const HEIGHT: u32 = 19;
+
+/// The width of the map of the world on the left of the window.
+const MAP_WIDTH: u32 = 31;
+
+/// The column the inventory and status panel starts at.
+const PANEL_X: u32 = MAP_WIDTH + 1;
+
+
+/// A window following a single turtle.
+pub struct Viewer {
+	pub term: Terminal,
+	pub turtle: u32,
+}
+
+
+impl Viewer {
+
+	/// Create a viewer window for the turtle with the given ID.
+	pub fn new(parent: &Minion, turtle: u32, border_width: u32, border_height: u32) -> Viewer {
+		let mut term = Terminal::from_parent(
+			&parent.term,
+			format!("World - Turtle {}", turtle).as_slice(),
+			WIDTH,
+			HEIGHT,
+			border_width,
+			border_height
+		);
+		term.cursor_visibility(false);
+
+		Viewer {
+			term: term,
+			turtle: turtle,
+		}
+	}
+
+	/// Write a string with colors, clipped to the window's width.
+	fn write(&mut self, text: &str, x: u32, y: u32, foreground: u32, background: u32) {
+		for (offset, character) in text.chars().enumerate() {
+			let cell_x = x + offset as u32;
+			if cell_x >= WIDTH {
+				break;
+			}
+
+			self.term.character(character, cell_x, y);
+			self.term.foreground(foreground, cell_x, y);
+			self.term.background(background, cell_x, y);
+		}
+	}
+
+	/// Draw the top down map of the world around the turtle, at its height.
+	fn draw_map(&mut self, turtle: &Minion) {
+		let rendered = turtle.render_world(MAP_WIDTH, HEIGHT);
+		for (y, line) in rendered.as_slice().lines().enumerate() {
+			let parts: Vec<&str> = line.split('\t').collect();
+			if parts.len() != 3 {
+				continue;
+			}
+
+			let colors = parts[1].chars().zip(parts[2].chars());
+			for (x, (character, (foreground, background))) in parts[0].chars().zip(colors).enumerate() {
+				self.term.character(character, x as u32, y as u32);
+				self.term.foreground(color::character_to_hex(foreground), x as u32, y as u32);
+				self.term.background(color::character_to_hex(background), x as u32, y as u32);
+			}
+		}
+
+		// Separate the map from the panel
+		for y in range(0, HEIGHT) {
+			self.write("|", MAP_WIDTH, y, color::GRAY, color::BLACK);
+		}
+	}
+
+	/// Draw the turtle's position, fuel and inventory grid. Each slot shows
+	/// the item's symbol from the map and its count, with the selected slot
+	/// highlighted, and the selected item's name below.
+	fn draw_panel(&mut self, turtle: &Minion) {
+		let blank: String = range(PANEL_X, WIDTH).map(|_| ' ').collect();
+		for y in range(0, HEIGHT) {
+			self.write(blank.as_slice(), PANEL_X + 1, y, color::WHITE, color::BLACK);
+		}
+
+		let title = format!("Turtle {}", self.turtle);
+		let position = turtle.turtle_position();
+		let status = format!("{}, fuel {}", turtle.turtle_direction(), turtle.turtle_fuel());
+		self.write(title.as_slice(), PANEL_X + 1, 0, color::YELLOW, color::BLACK);
+		self.write(position.as_slice(), PANEL_X + 1, 1, color::WHITE, color::BLACK);
+		self.write(status.as_slice(), PANEL_X + 1, 2, color::WHITE, color::BLACK);
+
+		let selected = turtle.turtle_selected_slot();
+		for slot in range(1, 17) {
+			let column = (slot - 1) % 4;
+			let row = (slot - 1) / 4;
+			let cell_x = PANEL_X + 1 + column * 4;
+			let cell_y = 4 + row * 2;
+			let background = if slot == selected { color::GRAY } else { color::BLACK };
+
+			let item = turtle.turtle_item(slot);
+			if item.len() == 0 {
+				self.write(" .  ", cell_x, cell_y, color::GRAY, background);
+				continue;
+			}
+
+			let symbol: Vec<char> = turtle.item_symbol(item.as_slice()).chars().collect();
+			let symbol_text = symbol[0].to_string();
+			self.write(symbol_text.as_slice(), cell_x, cell_y,
+				color::character_to_hex(symbol[1]), color::character_to_hex(symbol[2]));
+
+			let count = format!("{:>2} ", turtle.turtle_item_count(slot));
+			self.write(count.as_slice(), cell_x + 1, cell_y, color::WHITE, background);
+		}
+
+		let item = turtle.turtle_item(selected);
+		let description = if item.len() == 0 {
+			format!("Slot {}: empty", selected)
+		} else {
+			format!("Slot {}: {}", selected, turtle.turtle_item_count(selected))
+		};
+		self.write(description.as_slice(), PANEL_X + 1, 13, color::WHITE, color::BLACK);
+		self.write(item.as_slice(), PANEL_X + 1, 14, color::LIGHT_GRAY, color::BLACK);
+	}
+
+	/// Redraw the window from the turtle's current state.
+	pub fn update(&mut self, turtle: &Minion) {
+		self.draw_map(turtle);
+		self.draw_panel(turtle);
+		self.term.events();
+	}
+
+}